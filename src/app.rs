@@ -1,28 +1,99 @@
-use std::io::{self};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::panic;
+use std::time::{Duration, Instant};
 
+use crate::catalog::Catalog;
+use crate::config::Settings;
 use crate::database::{
-    AVAILABLE_SHEME, CLASSIC_SHEME, CUSTOM_SHEME, Database, FILE_SCHEME, MONGODB_SCHEME,
-    REDIS_SCHEME, SOCKET_SCHEME, insert_default_config
+    AVAILABLE_SHEME, Database, add_history, copy_to_profile, delete_workspace, escape,
+    get_database_path, get_history, get_profile_path, get_profiles, get_rules, get_scheme,
+    get_secrets, get_trash, get_workspaces, insert_default_config, is_local, move_to_trash,
+    purge_trash, restore_from_trash, revert_to_catalog, save_configurations, save_workspace,
+    set_database_path,
 };
+use crate::editor::{
+    Editor, shortcut_from_toml, shortcut_to_toml, with_error, workspace_from_toml,
+    workspace_to_toml,
+};
+use crate::error::{Error, Result};
+use crate::keymap::{Action, Context, Keymap};
+use crate::launcher::{DEFAULT_LAYOUT, DEFAULT_WINDOW, OpenMode, open_workspace};
+use crate::logs::Logs;
 use crate::objects::*;
-use crate::ui::{Common, WidgetConfigurations, WidgetShortcuts, render_help, render_pop_up};
+use crate::shell::{Shell, ShellCommand};
+use crate::theme::{Theme, load_themes};
+use crate::ui::{
+    Common, NARROW_WIDTH, PREVIEW_HEIGHT, STATUS_BAR_HEIGHT, WidgetConfigurations, WidgetShortcuts,
+    help_height, pop_up_row_at, render_alert, render_confirmation, render_help, render_history,
+    render_open_menu, render_pop_up, render_preview, render_profiles, render_status_bar,
+    render_toasts, render_trash, render_workspaces, row_at, visible_rows,
+};
 use crate::utils::*;
 use crate::{filter_config, format_config};
 
 use ratatui::{
-    DefaultTerminal, Frame,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    crossterm::{
+        cursor::Show,
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyEvent, KeyEventKind,
+            MouseButton, MouseEvent, MouseEventKind,
+        },
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
+    layout::{Position, Rect},
     prelude::{Constraint, Direction, Layout},
     widgets::TableState,
 };
 use tui_input::Input;
 use tui_input::backend::crossterm::EventHandler;
 
-pub fn main_app() -> io::Result<String> {
-    let mut terminal = ratatui::init();
-    let mut app = App::new();
+/// Maximum delay between the two clicks of a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// How long a toast, or the message of the status bar, stays on the screen.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// How often the screen is redrawn while a toast is shown.
+const TOAST_TICK: Duration = Duration::from_millis(250);
+/// Number of entries shown in the history view.
+const HISTORY_LIMIT: usize = 100;
+
+/// The terminal where the app is drawn.
+pub type Tui = Terminal<CrosstermBackend<Box<dyn Write>>>;
+
+/// How the app ended.
+pub enum Outcome {
+    /// The command the shell must run, empty when nothing was chosen
+    Command(String),
+    /// The exit code of the command run by the app, with `exec`
+    Exited(i32),
+}
+
+/// Run the app and get the command chosen by the user.<br>
+/// With `tty` the app is drawn on `/dev/tty`, so the standard output only gets the command.
+/// With `exec` the app runs the command itself. `profile` is the active profile, there is none
+/// with `--db`.
+pub fn main_app(
+    shell: Shell,
+    tty: bool,
+    exec: bool,
+    profile: Option<String>,
+) -> io::Result<Outcome> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if let Ok(mut output) = open_output(tty) {
+            leave(&mut output);
+        }
+        hook(info);
+    }));
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(open_output(tty)?))?;
+    resume(&mut terminal)?;
+    let mut app = App::new(shell, exec, profile);
     let app_result = app.run(&mut terminal);
-    ratatui::restore();
+    suspend(&mut terminal);
     app_result
 }
 
@@ -32,88 +103,588 @@ pub struct App {
     /// The name of the Shortcut before any modification
     save: String,
     show_pop_up: (bool, usize),
+    /// Destructive action waiting for a confirmation, with the message describing it
+    confirmation: Option<(Pending, String)>,
+    /// Message shown in a modal until the user closes it
+    alert: Option<String>,
+    /// Errors shown in the corner of the screen for a few seconds, with when they happened
+    toasts: Vec<(String, Instant)>,
+    /// Message of the status bar, whether it's an error and when it was set
+    status: Option<(String, bool, Instant)>,
+    show_trash: (bool, usize),
+    trash: Vec<TrashEntry>,
+    show_workspaces: (bool, usize),
+    workspaces: Vec<Workspace>,
+    show_history: (bool, usize),
+    history: Vec<HistoryEntry>,
+    show_profiles: (bool, usize),
+    profiles: Vec<String>,
+    /// The profile whose database is used, there is none with `--db`
+    profile: Option<String>,
+    /// The history entry of the shortcut being opened, its command and the index of the
+    /// selected mode
+    open_menu: Option<(HistoryEntry, ShellCommand, usize)>,
+    /// The modes of `open_menu`, the menu is skipped when only the current shell is available
+    open_modes: Vec<OpenMode>,
+    settings: Settings,
+    /// The shared shortcuts merged under the local ones
+    catalog: Catalog,
+    /// Run the commands instead of returning them
+    exec: bool,
+    /// The history entry of the shortcut, or the workspace, whose command is returned.<br>
+    /// It's recorded once the command is returned, or run with its exit code.
+    opened: Option<HistoryEntry>,
+    /// Multi-line editor of the selected property
+    editor: Option<Editor>,
+    /// The content of the editor, or the selected shortcut, must be opened in `$EDITOR`
+    /// before the next frame
+    external: bool,
+    keymap: Keymap,
+    /// The commands are written in the syntax of this shell
+    shell: Shell,
+    /// The available themes and the index of the active one
+    themes: (Vec<Theme>, usize),
+    show_help: bool,
+    areas: Areas,
+    /// The time and the position of the last click
+    last_click: Option<(Instant, Position)>,
     exit: bool,
 }
 
+/// Where the widgets were drawn in the last frame, used to map the mouse events.
+#[derive(Debug, Default)]
+struct Areas {
+    shortcuts: Rect,
+    configurations: Rect,
+    pop_up: Rect,
+    pop_up_offset: usize,
+}
+
+/// Destructive actions that need to be confirmed by the user.
+enum Pending {
+    DeleteShortcut(String),
+    /// Delete the local values of a shortcut of the catalog
+    RevertShortcut(String),
+    /// The name of the Shortcut and the index of the property
+    ClearProperty(String, usize),
+    /// Purge one entry of the trash or the whole trash
+    PurgeTrash(Option<i64>),
+    DeleteWorkspace(String),
+}
+
 impl App {
-    pub fn new() -> Self {
-        App {
-            shortcuts: WidgetShortcuts::from(
-                vec![],
-                State::Selected(TableState::new().with_selected(0).with_selected_column(1)),
-            ),
+    pub fn new(shell: Shell, exec: bool, profile: Option<String>) -> Self {
+        let settings = Settings::load();
+        let mut shortcuts = WidgetShortcuts::from(
+            vec![],
+            State::Selected(TableState::new().with_selected(0).with_selected_column(1)),
+        );
+        shortcuts.set_profile(profile.as_deref());
+        let mut app = App {
+            shortcuts,
             configurations: WidgetConfigurations::from(
                 vec![],
                 State::WasSelected(TableState::new().with_selected(0)),
             ),
             save: String::new(),
             show_pop_up: (false, 0usize),
+            confirmation: None,
+            alert: None,
+            toasts: Vec::new(),
+            status: None,
+            show_trash: (false, 0usize),
+            trash: vec![],
+            show_workspaces: (false, 0usize),
+            show_history: (false, 0usize),
+            history: Vec::new(),
+            show_profiles: (false, 0usize),
+            profiles: vec![],
+            profile,
+            workspaces: vec![],
+            open_menu: None,
+            open_modes: OpenMode::available(&settings),
+            settings,
+            catalog: Catalog::default(),
+            exec,
+            opened: None,
+            editor: None,
+            external: false,
+            keymap: Keymap::load(),
+            shell,
+            themes: load_themes(),
+            show_help: true,
+            areas: Areas::default(),
+            last_click: None,
             exit: false,
+        };
+        if let Some(path) = app.settings.get_catalog().cloned() {
+            match Catalog::load(&path) {
+                Ok(catalog) => app.catalog = catalog,
+                Err(error) => app.report("app.rs - App::new()", error),
+            }
         }
+        app
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<String> {
+    pub fn run(&mut self, terminal: &mut Tui) -> io::Result<Outcome> {
         while !self.exit {
+            self.toasts
+                .retain(|(_, shown_at)| shown_at.elapsed() < TOAST_DURATION);
+            if self
+                .status
+                .as_ref()
+                .is_some_and(|(_, _, shown_at)| shown_at.elapsed() >= TOAST_DURATION)
+            {
+                self.status = None;
+            }
             self.update_widgets_args();
             terminal.draw(|frame| self.draw(frame))?;
             match self.handle_events() {
+                Ok(message) if !message.is_empty() && self.exec => {
+                    if let Some(code) = self.execute(terminal, &message)? {
+                        return Ok(Outcome::Exited(code));
+                    }
+                }
                 Ok(message) => {
                     if !message.is_empty() {
-                        return Ok(message);
+                        if let Some(entry) = self.opened.take() {
+                            record(&entry);
+                        }
+                        return Ok(Outcome::Command(message));
                     }
                 }
                 Err(error) => {
                     return Err(error);
                 }
             };
+            if self.external {
+                self.external = false;
+                if self.editor.is_some() {
+                    self.edit_externally(terminal)?;
+                } else if self.show_workspaces.0 {
+                    self.edit_workspace_externally(terminal)?;
+                } else {
+                    self.edit_shortcut_externally(terminal)?;
+                }
+            }
         }
-        Ok("".to_owned())
+        Ok(Outcome::Command(String::new()))
+    }
+
+    /// Suspend the TUI to run the command.<br>
+    /// When it fails the user can go back to the app to fix the shortcut, then `None` is returned.
+    fn execute(&mut self, terminal: &mut Tui, command: &str) -> io::Result<Option<i32>> {
+        let mut entry = self.opened.take();
+        let name = entry
+            .as_ref()
+            .map(|e| String::clone(e.get_name()))
+            .unwrap_or_default();
+        suspend(terminal);
+        let code = match execute(&self.shell.invocation(command)) {
+            Ok(code) => code,
+            Err(error) => {
+                eprintln!("{}", error);
+                127
+            }
+        };
+        Logs::info(
+            "app.rs - execute()",
+            format!("'{}' exited with code {}", name, code),
+        );
+        if let Some(entry) = entry.as_mut() {
+            entry.set_exit_code(code);
+            record(entry);
+        }
+        if code == 0 {
+            return Ok(Some(0));
+        }
+
+        eprint!(
+            "\n'{}' exited with code {}. Press Enter to go back to MyShortcuts, or q to quit : ",
+            name, code
+        );
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if answer.trim().eq_ignore_ascii_case("q") {
+            return Ok(Some(code));
+        }
+        self.exit = false;
+        self.alert = Some(format!(
+            "'{}' exited with code {}. Fix the shortcut and open it again.",
+            name, code
+        ));
+        resume(terminal)?;
+        Ok(None)
+    }
+
+    /// Suspend the TUI to edit the content of the editor in `$EDITOR`.
+    fn edit_externally(&mut self, terminal: &mut Tui) -> io::Result<()> {
+        let Some(editor) = self.editor.as_mut() else {
+            return Ok(());
+        };
+        suspend(terminal);
+        match edit_externally(&editor.get_value(), "sh") {
+            Ok(value) => {
+                editor.set_value(&value);
+                editor.set_error(None);
+            }
+            Err(error) => editor.set_error(Some(error.to_string())),
+        }
+        resume(terminal)
+    }
+
+    /// Suspend the TUI to edit the selected shortcut as a TOML file in `$EDITOR`.<br>
+    /// The file is opened again with the error until it's valid, or left unchanged.
+    fn edit_shortcut_externally(&mut self, terminal: &mut Tui) -> io::Result<()> {
+        let Some(shortcut) = self.get_selected_shortcut().cloned() else {
+            return Ok(());
+        };
+        let configurations = self.configurations.get_values().clone();
+        let rules = get_rules(shortcut.get_kind());
+        let names = self
+            .shortcuts
+            .get_values()
+            .iter()
+            .map(|s| String::clone(s.get_name()))
+            .collect::<Vec<String>>();
+
+        suspend(terminal);
+        let mut document = shortcut_to_toml(&shortcut, &configurations);
+        loop {
+            let content = match edit_externally(&document, "toml") {
+                Ok(content) => content,
+                Err(error) => {
+                    self.alert = Some(error.to_string());
+                    break;
+                }
+            };
+            if content.trim_end() == document.trim_end() {
+                break;
+            }
+            let parsed = shortcut_from_toml(&content, &shortcut, &configurations, &rules, &names)
+                .and_then(|(name, new_configurations)| {
+                    if name != *shortcut.get_name() && self.is_from_catalog(shortcut.get_name()) {
+                        Err(from_catalog(shortcut.get_name(), "renamed"))
+                    } else {
+                        Ok((name, new_configurations))
+                    }
+                });
+            match parsed {
+                Ok((name, new_configurations)) => {
+                    // A new password must not be written in the logs of the query
+                    Logs::add_secrets(&get_secrets(&new_configurations));
+                    let result = if name == *shortcut.get_name() {
+                        save_configurations(
+                            &name,
+                            shortcut.get_kind(),
+                            &format_config!(&new_configurations),
+                        )
+                    } else {
+                        Database::query_write(&format!(
+                            "update shortcuts set name='{}', configuration='{}' where name='{}';
+                            update workspace_panes set shortcut='{}' where shortcut='{}';",
+                            escape(&name),
+                            escape(&format_config!(&new_configurations)),
+                            escape(shortcut.get_name()),
+                            escape(&name),
+                            escape(shortcut.get_name())
+                        ))
+                    };
+                    match result {
+                        Ok(()) => self.notify(format!("Saved '{}'", name)),
+                        Err(error) => self.report("app.rs - edit_shortcut_externally()", error),
+                    }
+                    break;
+                }
+                Err(error) => document = with_error(&content, &error.to_string()),
+            }
+        }
+        resume(terminal)
+    }
+
+    /// Suspend the TUI to edit the selected workspace as a TOML file in `$EDITOR`.
+    fn edit_workspace_externally(&mut self, terminal: &mut Tui) -> io::Result<()> {
+        let Some(workspace) = self.workspaces.get(self.show_workspaces.1).cloned() else {
+            return Ok(());
+        };
+        let names = self
+            .workspaces
+            .iter()
+            .map(|w| String::clone(w.get_name()))
+            .collect::<Vec<String>>();
+        let shortcuts = self
+            .shortcuts
+            .get_values()
+            .iter()
+            .map(|s| String::clone(s.get_name()))
+            .collect::<Vec<String>>();
+
+        suspend(terminal);
+        let mut document = workspace_to_toml(&workspace);
+        loop {
+            let content = match edit_externally(&document, "toml") {
+                Ok(content) => content,
+                Err(error) => {
+                    self.alert = Some(error.to_string());
+                    break;
+                }
+            };
+            if content.trim_end() == document.trim_end() {
+                break;
+            }
+            match workspace_from_toml(&content, &workspace, &names, &shortcuts) {
+                Ok(new_workspace) => {
+                    match save_workspace(&new_workspace, Some(workspace.get_name())) {
+                        Ok(_) => self.notify(format!(
+                            "Saved the workspace '{}'",
+                            new_workspace.get_name()
+                        )),
+                        Err(error) => self.report("app.rs - edit_workspace_externally()", error),
+                    }
+                    break;
+                }
+                Err(error) => document = with_error(&content, &error.to_string()),
+            }
+        }
+        resume(terminal)
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let layout0 = Layout::new(
-            Direction::Horizontal,
-            [Constraint::Percentage(30), Constraint::Percentage(100)],
+        let theme = self.themes.0[self.themes.1].clone();
+        let layout = Layout::new(
+            Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(STATUS_BAR_HEIGHT)],
         )
         .split(frame.area());
+        let (area, status_bar) = (layout[0], layout[1]);
+        // The help is hidden when it would leave too little room to the Shortcuts
+        let help_height = if self.show_help && area.height >= 2 * help_height() {
+            help_height()
+        } else {
+            0
+        };
 
-        let layout1 = Layout::new(
-            Direction::Vertical,
-            [Constraint::Percentage(80), Constraint::Percentage(20)],
-        )
-        .split(layout0[0]);
+        // The panes are side by side, or stacked when the terminal is narrow
+        let (shortcuts, right, help) = if area.width < NARROW_WIDTH {
+            let layout0 = Layout::new(
+                Direction::Vertical,
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Min(0),
+                    Constraint::Length(help_height),
+                ],
+            )
+            .split(area);
+            (layout0[0], layout0[1], layout0[2])
+        } else {
+            let layout0 = Layout::new(
+                Direction::Horizontal,
+                [Constraint::Percentage(30), Constraint::Percentage(100)],
+            )
+            .split(area);
+            let layout1 = Layout::new(
+                Direction::Vertical,
+                [Constraint::Min(0), Constraint::Length(help_height)],
+            )
+            .split(layout0[0]);
+            (layout1[0], layout0[1], layout1[1])
+        };
 
-        render_help(frame, layout1[1]);
-        self.shortcuts.render(frame, layout1[0]);
+        if help_height > 0 {
+            render_help(frame, help, &self.keymap, &theme);
+        }
+        self.shortcuts.render(frame, shortcuts, &theme);
+        self.areas.shortcuts = shortcuts;
 
-        if self.show_pop_up.0 {
+        if self.show_pop_up.0 && area.width < NARROW_WIDTH {
+            // There is no room for the Configurations next to the pop-up
+            self.areas.pop_up_offset = render_pop_up(frame, self.show_pop_up.1, right, &theme);
+            self.areas.pop_up = right;
+            self.areas.configurations = Rect::default();
+        } else if self.show_pop_up.0 {
             let layout2 = Layout::new(
                 Direction::Horizontal,
                 [Constraint::Percentage(50), Constraint::Percentage(50)],
             )
-            .split(layout0[1]);
-            render_pop_up(frame, self.show_pop_up.1, layout2[0]);
-            self.configurations.render(frame, layout2[1]);
+            .split(right);
+            self.areas.pop_up_offset = render_pop_up(frame, self.show_pop_up.1, layout2[0], &theme);
+            self.areas.pop_up = layout2[0];
+            self.render_configurations(frame, layout2[1], &theme);
         } else {
-            self.configurations.render(frame, layout0[1]);
+            self.areas.pop_up = Rect::default();
+            self.render_configurations(frame, right, &theme);
+        }
+
+        if self.show_trash.0 {
+            render_trash(
+                frame,
+                &self.trash,
+                self.show_trash.1,
+                right,
+                &self.keymap,
+                &theme,
+            );
+        }
+        if self.show_workspaces.0 {
+            render_workspaces(
+                frame,
+                &self.workspaces,
+                self.show_workspaces.1,
+                right,
+                &self.keymap,
+                &theme,
+            );
+        }
+        if self.show_history.0 {
+            render_history(
+                frame,
+                &self.history,
+                self.show_history.1,
+                right,
+                &self.keymap,
+                &theme,
+            );
+        }
+        if self.show_profiles.0 {
+            render_profiles(
+                frame,
+                &self.profiles,
+                self.profile.as_deref(),
+                self.show_profiles.1,
+                right,
+                &self.keymap,
+                &theme,
+            );
+        }
+        if let Some((entry, _, index)) = &self.open_menu {
+            let modes = self
+                .open_modes
+                .iter()
+                .map(|mode| mode.get_name())
+                .collect::<Vec<&str>>();
+            render_open_menu(
+                frame,
+                entry.get_name(),
+                &modes,
+                *index,
+                &self.keymap,
+                &theme,
+            );
+        }
+        if let Some(editor) = self.editor.as_mut() {
+            editor.render(frame, &self.keymap, &theme);
+        }
+        if let Some((_, message)) = &self.confirmation {
+            render_confirmation(frame, "Confirmation", message, &self.keymap, &theme);
+        }
+        if let Some(message) = &self.alert {
+            render_alert(frame, "Warning", message, &self.keymap, &theme);
+        }
+        if !self.toasts.is_empty() {
+            let messages = self
+                .toasts
+                .iter()
+                .map(|(message, _)| message.as_str())
+                .collect::<Vec<&str>>();
+            render_toasts(frame, area, &messages, &theme);
+        }
+
+        let mode = if self.editor.is_some() {
+            Context::Editor
+        } else if matches!(self.shortcuts.get_state(), State::Editing(..))
+            || matches!(self.configurations.get_state(), State::Editing(..))
+        {
+            Context::Editing
+        } else {
+            self.get_context()
+        };
+        let database = get_database_path()
+            .map(|path| shorten_home(&path))
+            .unwrap_or_default();
+        render_status_bar(
+            frame,
+            status_bar,
+            mode.get_name(),
+            self.status
+                .as_ref()
+                .map(|(message, error, _)| (message.as_str(), *error)),
+            self.shortcuts.get_values().len(),
+            &database,
+            &theme,
+        );
+    }
+
+    /// Render the Configurations table with the preview of the command under it.
+    fn render_configurations(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        // The preview is dropped when the terminal is too short
+        let preview_height = if area.height >= 3 * PREVIEW_HEIGHT {
+            PREVIEW_HEIGHT
+        } else {
+            0
+        };
+        let layout = Layout::new(
+            Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(preview_height)],
+        )
+        .split(area);
+        self.configurations.render(frame, layout[0], theme);
+        self.areas.configurations = layout[0];
+
+        if preview_height > 0
+            && let Some(shortcut) = self.get_selected_shortcut()
+        {
+            match self.get_shortcut(String::clone(shortcut.get_kind())) {
+                Ok(mut command) => {
+                    if self.configurations.is_hidden() {
+                        command = command.mask(&self.configurations.get_secrets());
+                    }
+                    let command = command.to_string();
+                    let problem = get_problem(&command);
+                    render_preview(frame, layout[1], &command, problem, theme);
+                }
+                Err(missing) => {
+                    render_preview(frame, layout[1], "", Some(&missing.to_string()), theme)
+                }
+            }
         }
     }
 
     fn handle_events(&mut self) -> io::Result<String> {
+        // Wake up without any event so the toasts and the status disappear on time
+        if (!self.toasts.is_empty() || self.status.is_some()) && !event::poll(TOAST_TICK)? {
+            return Ok(String::new());
+        }
         let event = event::read()?;
-        if let Event::Key(key) = event {
+        if let Event::Key(key) = event
+            && self.editor.is_some()
+        {
+            if key.kind == KeyEventKind::Press {
+                self.handle_editor_key(key);
+            }
+        } else if let Event::Key(key) = event {
+            let editing = matches!(self.shortcuts.get_state(), State::Editing(..))
+                || matches!(self.configurations.get_state(), State::Editing(..));
+            let save = editing
+                && key.kind == KeyEventKind::Press
+                && self.keymap.resolve(Context::Editing, key) == Some(Action::Save);
+
             match (self.shortcuts.get_state(), self.configurations.get_state()) {
-                (State::Editing(ts0, input), State::WasSelected(_))
-                    if key.code == KeyCode::Enter =>
-                {
+                (State::Editing(ts0, input), State::WasSelected(_)) if save => {
                     self.save_editing(input.value().into(), true);
                     self.shortcuts.set_state(State::Selected(ts0));
                 }
-                (State::WasSelected(_), State::Editing(ts1, input))
-                    if key.code == KeyCode::Enter =>
-                {
-                    self.save_editing(input.value().into(), false);
-                    self.configurations.set_state(State::Selected(ts1));
+                (State::WasSelected(_), State::Editing(ts1, input)) if save => {
+                    // The value can't be saved while it's invalid, the error is shown inline
+                    let index1 = ts1.selected().unwrap_or(0);
+                    if self
+                        .configurations
+                        .get_errors()
+                        .get(index1)
+                        .is_none_or(|e| e.is_none())
+                    {
+                        self.save_editing(input.value().into(), false);
+                        self.configurations.set_state(State::Selected(ts1));
+                    }
                 }
                 (State::Editing(ts0, input), _) => {
                     let mut new_input = input;
@@ -133,17 +704,246 @@ impl App {
                 }
                 _ => {}
             }
+        } else if let Event::Mouse(mouse) = event
+            && let Some(message) = self.handle_mouse_event(mouse)
+        {
+            return Ok(message);
         }
         Ok("".to_owned())
     }
 
+    /// Send the key to the multi-line editor, unless it saves, cancels or opens $EDITOR.
+    fn handle_editor_key(&mut self, key: KeyEvent) {
+        match self.keymap.resolve(Context::Editor, key) {
+            Some(Action::Save) => self.save_editor(),
+            Some(Action::Cancel) => self.editor = None,
+            Some(Action::External) => self.external = true,
+            Some(_) => {}
+            None => {
+                if let Some(editor) = self.editor.as_mut() {
+                    editor.handle_key(key);
+                }
+            }
+        }
+    }
+
+    /// Save the value of the editor in the selected property, unless it's invalid.
+    fn save_editor(&mut self) {
+        let (Some(editor), State::WasSelected(ts0), State::Selected(ts1)) = (
+            self.editor.as_mut(),
+            self.shortcuts.get_state(),
+            self.configurations.get_state(),
+        ) else {
+            return;
+        };
+        let (Some(index0), Some(index1)) = (ts0.selected(), ts1.selected()) else {
+            return;
+        };
+        let Some(shortcut) = self.shortcuts.get_values().get(index0) else {
+            return;
+        };
+
+        let value = editor.get_value();
+        if let Some(rule) = get_rules(shortcut.get_kind()).get(index1)
+            && let Err(error) = rule.validate(&value)
+        {
+            editor.set_error(Some(error.to_string()));
+            return;
+        }
+        self.save = String::clone(shortcut.get_name());
+        if let Some(configuration) = self.configurations.get_mut_values().get_mut(index1) {
+            configuration.set_value(&value);
+            self.save_editing(value, false);
+        }
+        self.editor = None;
+    }
+
+    /// Select the row under a click, a double-click opens the shortcut or edits the property.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Option<String> {
+        if self.alert.is_some()
+            || self.confirmation.is_some()
+            || self.show_trash.0
+            || self.show_workspaces.0
+            || self.show_history.0
+            || self.show_profiles.0
+            || self.open_menu.is_some()
+            || self.editor.is_some()
+        {
+            return None;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        let click = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        let double_click = click && self.is_double_click(position);
+
+        if self.show_pop_up.0 {
+            if !self.areas.pop_up.contains(position) {
+                return None;
+            }
+            return match mouse.kind {
+                _ if click => {
+                    let index =
+                        pop_up_row_at(self.areas.pop_up, self.areas.pop_up_offset, mouse.row)?;
+                    self.show_pop_up.1 = index;
+                    self.handle_action(Action::Select)
+                }
+                MouseEventKind::ScrollUp => self.handle_action(Action::Up),
+                MouseEventKind::ScrollDown => self.handle_action(Action::Down),
+                _ => None,
+            };
+        }
+
+        let (State::Selected(mut ts0) | State::WasSelected(mut ts0)) = self.shortcuts.get_state()
+        else {
+            return None;
+        };
+        let (State::Selected(mut ts1) | State::WasSelected(mut ts1)) =
+            self.configurations.get_state()
+        else {
+            return None;
+        };
+
+        if self.areas.shortcuts.contains(position) {
+            match mouse.kind {
+                _ if click => {
+                    let len = self.shortcuts.get_values().len();
+                    let index = row_at(
+                        self.areas.shortcuts,
+                        self.shortcuts.is_compact(),
+                        ts0.offset(),
+                        len,
+                        mouse.row,
+                    )?;
+                    ts0.select(Some(index));
+                    ts0.select_column(Some(1));
+                    self.shortcuts.set_state(State::Selected(ts0));
+                    self.configurations.set_state(State::WasSelected(ts1));
+                    if double_click {
+                        return self.handle_action(Action::Open);
+                    }
+                }
+                MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                    let len = self.shortcuts.get_values().len();
+                    let down = mouse.kind == MouseEventKind::ScrollDown;
+                    self.shortcuts
+                        .set_state(scroll_state(self.shortcuts.get_state(), len, down));
+                }
+                _ => {}
+            }
+        } else if self.areas.configurations.contains(position) {
+            match mouse.kind {
+                _ if click => {
+                    let len = self.configurations.get_values().len();
+                    let index = row_at(
+                        self.areas.configurations,
+                        self.configurations.is_compact(),
+                        ts1.offset(),
+                        len,
+                        mouse.row,
+                    )?;
+                    ts1.select(Some(index));
+                    ts1.select_column(Some(1));
+                    ts0.select_column(Some(1));
+                    self.shortcuts.set_state(State::WasSelected(ts0));
+                    self.configurations.set_state(State::Selected(ts1));
+                    if double_click {
+                        return self.handle_action(Action::Edit);
+                    }
+                }
+                MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                    let len = self.configurations.get_values().len();
+                    let down = mouse.kind == MouseEventKind::ScrollDown;
+                    self.configurations.set_state(scroll_state(
+                        self.configurations.get_state(),
+                        len,
+                        down,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Check if the click at this position follows another one, a triple-click isn't a double-click.
+    fn is_double_click(&mut self, position: Position) -> bool {
+        let now = Instant::now();
+        match self.last_click.take() {
+            Some((time, last)) if last == position && now.duration_since(time) <= DOUBLE_CLICK => {
+                true
+            }
+            _ => {
+                self.last_click = Some((now, position));
+                false
+            }
+        }
+    }
+
+    /// Get the context of the keys, outside of the editors.
+    fn get_context(&self) -> Context {
+        if self.alert.is_some() {
+            Context::Alert
+        } else if self.confirmation.is_some() {
+            Context::Confirmation
+        } else if self.show_trash.0 {
+            Context::Trash
+        } else if self.show_workspaces.0 {
+            Context::Workspaces
+        } else if self.show_history.0 {
+            Context::History
+        } else if self.show_profiles.0 {
+            Context::Profiles
+        } else if self.show_pop_up.0 || self.open_menu.is_some() {
+            Context::PopUp
+        } else {
+            Context::Normal
+        }
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<String> {
+        let action = self.keymap.resolve(self.get_context(), key_event)?;
+
+        if self.alert.is_some() {
+            if action == Action::Close {
+                self.alert = None;
+            }
+            return None;
+        }
+        if let Some((pending, message)) = self.confirmation.take() {
+            match action {
+                Action::Confirm => self.confirm(pending),
+                Action::Cancel => {}
+                _ => self.confirmation = Some((pending, message)),
+            }
+            return None;
+        }
+        if self.show_trash.0 {
+            self.handle_trash_action(action);
+            return None;
+        }
+        if self.show_workspaces.0 {
+            return self.handle_workspaces_action(action);
+        }
+        if self.show_history.0 {
+            self.handle_history_action(action);
+            return None;
+        }
+        if self.show_profiles.0 {
+            self.handle_profiles_action(action);
+            return None;
+        }
+        if self.open_menu.is_some() {
+            return self.handle_open_action(action);
+        }
+        self.handle_action(action)
+    }
+
+    fn handle_action(&mut self, action: Action) -> Option<String> {
         match (
             self.shortcuts.get_state(),
             self.configurations.get_state(),
-            key_event.code,
+            action,
         ) {
-            (State::Selected(mut ts), State::WasSelected(_), KeyCode::Up) => {
+            (State::Selected(mut ts), State::WasSelected(_), Action::Up) => {
                 let index = ts.selected().map_or(0, |i| {
                     if i > 0 {
                         i - 1
@@ -153,9 +953,8 @@ impl App {
                 });
                 ts.select(Some(index));
                 self.shortcuts.set_state(State::Selected(ts));
-                // ScrollBar interaction here
             }
-            (State::Selected(mut ts), State::WasSelected(_), KeyCode::Down) => {
+            (State::Selected(mut ts), State::WasSelected(_), Action::Down) => {
                 let index = ts.selected().map_or(0, |i| {
                     if i < self.shortcuts.get_values().len() - 1 {
                         i + 1
@@ -165,15 +964,24 @@ impl App {
                 });
                 ts.select(Some(index));
                 self.shortcuts.set_state(State::Selected(ts));
-                // ScrollBar interaction here
             }
-            (State::Selected(ts0), State::WasSelected(ts1), KeyCode::Right) => {
+            (
+                State::Selected(mut ts),
+                State::WasSelected(_),
+                Action::PageUp | Action::PageDown | Action::First | Action::Last,
+            ) => {
+                let page = visible_rows(self.areas.shortcuts, self.shortcuts.is_compact());
+                let len = self.shortcuts.get_values().len();
+                ts.select(Some(jump(ts.selected(), len, page, action)));
+                self.shortcuts.set_state(State::Selected(ts));
+            }
+            (State::Selected(ts0), State::WasSelected(ts1), Action::Right) => {
                 self.switch_selected_widget(ts0, ts1, true);
             }
-            (State::Selected(_), State::WasSelected(_), KeyCode::Left) => {
+            (State::Selected(_), State::WasSelected(_), Action::Left) => {
                 // Do nothing
             }
-            (State::WasSelected(_), State::Selected(mut ts), KeyCode::Up) => {
+            (State::WasSelected(_), State::Selected(mut ts), Action::Up) => {
                 let index = ts.selected().map_or(0, |i| {
                     if i > 0 {
                         i - 1
@@ -183,9 +991,8 @@ impl App {
                 });
                 ts.select(Some(index));
                 self.configurations.set_state(State::Selected(ts));
-                // ScrollBar interaction here
             }
-            (State::WasSelected(_), State::Selected(mut ts), KeyCode::Down) => {
+            (State::WasSelected(_), State::Selected(mut ts), Action::Down) => {
                 let index = ts.selected().map_or(0, |i| {
                     if i < self.configurations.get_values().len() - 1 {
                         i + 1
@@ -195,13 +1002,23 @@ impl App {
                 });
                 ts.select(Some(index));
                 self.configurations.set_state(State::Selected(ts));
-                // ScrollBar interaction here
             }
-            (State::WasSelected(ts0), State::Selected(ts1), KeyCode::Left) => {
+            (
+                State::WasSelected(_),
+                State::Selected(mut ts),
+                Action::PageUp | Action::PageDown | Action::First | Action::Last,
+            ) => {
+                let page =
+                    visible_rows(self.areas.configurations, self.configurations.is_compact());
+                let len = self.configurations.get_values().len();
+                ts.select(Some(jump(ts.selected(), len, page, action)));
+                self.configurations.set_state(State::Selected(ts));
+            }
+            (State::WasSelected(ts0), State::Selected(ts1), Action::Left) => {
                 self.switch_selected_widget(ts0, ts1, false);
             }
-            (State::WasSelected(_), State::Selected(mut ts1), KeyCode::Right) => {
-                if None == ts1.selected_column() {
+            (State::WasSelected(_), State::Selected(mut ts1), Action::Right) => {
+                if ts1.selected_column().is_none() {
                     ts1.select_column(Some(1));
                     self.configurations.set_state(State::Selected(ts1))
                 }
@@ -209,123 +1026,187 @@ impl App {
             (
                 State::Selected(ts0) | State::WasSelected(ts0),
                 State::WasSelected(_) | State::Selected(_),
-                KeyCode::Char('o') | KeyCode::Char('O'),
+                Action::Open,
             ) => {
-                self.exit();
                 if let Some(shortcut) = self.shortcuts.get_values().get(ts0.selected().unwrap_or(0))
                 {
-                    let command: String = self.get_shortcut(String::clone(shortcut.get_kind()));
-                    return Some(command);
+                    match self.get_shortcut(String::clone(shortcut.get_kind())) {
+                        Ok(command) => {
+                            let rendered = self.shell.render(&command);
+                            let entry = HistoryEntry::from(
+                                shortcut.get_name(),
+                                shortcut.get_kind(),
+                                OpenMode::Current.get_name(),
+                                &self
+                                    .shell
+                                    .render(&command.mask(&self.configurations.get_secrets())),
+                            );
+                            if self.open_modes.len() > 1 {
+                                self.open_menu = Some((entry, command, 0));
+                            } else {
+                                self.opened = Some(entry);
+                                self.exit();
+                                return Some(rendered);
+                            }
+                        }
+                        Err(missing) => {
+                            self.alert = Some(format!(
+                                "'{}' can't be opened. {}",
+                                shortcut.get_name(),
+                                missing
+                            ))
+                        }
+                    }
                 }
             }
-            (
-                State::Selected(index),
-                State::WasSelected(_),
-                KeyCode::Char('a') | KeyCode::Char('A'),
-            ) => {
+            (State::Selected(index), State::WasSelected(_), Action::Add) => {
                 self.shortcuts.set_state(State::WasSelected(index));
                 self.show_pop_up = (true, 0);
             }
-            (
-                State::WasSelected(_),
-                State::Selected(index),
-                KeyCode::Char('a') | KeyCode::Char('A'),
-            ) => {
+            (State::WasSelected(_), State::Selected(index), Action::Add) => {
                 self.configurations.set_state(State::WasSelected(index));
                 self.show_pop_up = (true, 0);
             }
-            (State::WasSelected(_), State::WasSelected(_), KeyCode::Up) => {
+            (State::WasSelected(_), State::WasSelected(_), Action::Up) => {
                 if self.show_pop_up.1 == 0 {
                     self.show_pop_up.1 = AVAILABLE_SHEME.len() - 1;
                 } else {
                     self.show_pop_up.1 -= 1;
                 }
             }
-            (State::WasSelected(_), State::WasSelected(_), KeyCode::Down) => {
+            (State::WasSelected(_), State::WasSelected(_), Action::Down) => {
                 self.show_pop_up = (true, (self.show_pop_up.1 + 1) % AVAILABLE_SHEME.len())
             }
-            (State::WasSelected(index), State::WasSelected(_), KeyCode::Enter) => {
+            (State::WasSelected(index), State::WasSelected(_), Action::Select) => {
                 self.add_new_shortcut();
                 self.show_pop_up = (false, 0);
                 self.shortcuts.set_state(State::Selected(index));
             }
-            (
-                State::Selected(mut ts0),
-                State::WasSelected(_),
-                KeyCode::Char('r') | KeyCode::Char('R'),
-            ) => {
-                if let Some(index0) = ts0.selected() {
-                    if let Some(shortcut) = self.shortcuts.get_values().get(index0) {
-                        let _ = Database::query_write(&format!(
-                            "delete from shortcuts where name='{}';",
-                            shortcut.get_name()
+            (State::Selected(ts0), State::WasSelected(_), Action::Remove) => {
+                if let Some(index0) = ts0.selected()
+                    && let Some(shortcut) = self.shortcuts.get_values().get(index0)
+                {
+                    if self.is_from_catalog(shortcut.get_name()) {
+                        let error = from_catalog(shortcut.get_name(), "deleted");
+                        self.report("app.rs - handle_action()", error);
+                        return None;
+                    }
+                    if self.catalog.overrides(shortcut) {
+                        self.confirmation = Some((
+                            Pending::RevertShortcut(String::clone(shortcut.get_name())),
+                            format!(
+                                "Revert the shortcut '{}' to the catalog ? Its local values will be lost.",
+                                shortcut.get_name()
+                            ),
                         ));
-                        if self.shortcuts.get_values().len() == 1 {
-                            if let Err(error) = Database::query_write(
-                                "insert into shortcuts values ('Default0', 'echo Welcome on MyShortcuts !', 'Custom');",
-                            ) {
-                                Logs::write(format!(
-                                    "ERROR : app.rs - handle_key_event() -1st :\n{}",
-                                    error
-                                ));
-                            }
-                        }
-                        ts0.select(Some(index0.saturating_sub(1)));
-                        self.shortcuts.set_state(State::Selected(ts0));
+                        return None;
                     }
+                    self.confirmation = Some((
+                        Pending::DeleteShortcut(String::clone(shortcut.get_name())),
+                        format!(
+                            "Delete the shortcut '{}' ({}) ? It will be moved to the trash.",
+                            shortcut.get_name(),
+                            shortcut.get_kind()
+                        ),
+                    ));
+                }
+            }
+            (State::WasSelected(ts0), State::Selected(ts1), Action::Remove) => {
+                if let (Some(index0), Some(index1)) = (ts0.selected(), ts1.selected())
+                    && let (Some(shortcut), Some(configuration)) = (
+                        self.shortcuts.get_values().get(index0),
+                        self.configurations.get_values().get(index1),
+                    )
+                {
+                    self.confirmation = Some((
+                        Pending::ClearProperty(String::clone(shortcut.get_name()), index1),
+                        format!(
+                            "Clear the property '{}' of '{}' ? Its current value will be lost.",
+                            configuration.get_kind(),
+                            shortcut.get_name()
+                        ),
+                    ));
                 }
             }
             (
-                State::WasSelected(ts0),
-                State::Selected(ts1),
-                KeyCode::Char('r') | KeyCode::Char('R'),
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Trash,
             ) => {
-                if let Some(index0) = ts0.selected() {
-                    if let Some(index1) = ts1.selected() {
-                        self.configurations.get_mut_values()[index1] = Configuration::from("", "");
-                        let query = format!(
-                            "update shortcuts set configuration='{}' where name='{}';",
-                            format_config!(self.configurations.get_values()),
-                            self.shortcuts.get_values()[index0].get_name()
-                        );
-                        let _ = Database::query_write(&query);
-                    }
+                self.show_trash = (true, 0);
+            }
+            (State::Selected(_), State::WasSelected(_), Action::Mark) => {
+                if let Some(shortcut) = self.get_selected_shortcut() {
+                    let name = String::clone(shortcut.get_name());
+                    self.shortcuts.toggle_mark(&name);
                 }
             }
             (
-                State::WasSelected(index),
-                State::WasSelected(_),
-                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc,
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Workspaces,
             ) => {
-                self.show_pop_up = (false, 0);
-                self.shortcuts.set_state(State::Selected(index));
+                self.show_workspaces = (true, 0);
             }
             (
-                State::Selected(mut ts0),
-                State::WasSelected(_),
-                KeyCode::Char('e') | KeyCode::Char('E'),
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::History,
             ) => {
-                if let Some(index) = ts0.selected() {
-                    if let Some(shortcut) = self.shortcuts.get_values().get(index) {
-                        self.save = String::clone(shortcut.get_name());
-                        ts0.select_column(Some(1));
-                        self.shortcuts.set_state(State::Editing(
-                            ts0,
-                            Input::with_value(Input::default(), String::clone(shortcut.get_name())),
-                        ));
-                    }
-                }
+                self.show_history = (true, 0);
             }
             (
-                State::WasSelected(ts0),
-                State::Selected(mut ts1),
-                KeyCode::Char('e') | KeyCode::Char('E'),
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Profiles,
             ) => {
+                match get_profiles() {
+                    Ok(profiles) => self.profiles = profiles,
+                    Err(error) => self.report("app.rs - handle_action() -profiles", error),
+                }
+                let index = self
+                    .profiles
+                    .iter()
+                    .position(|p| Some(p) == self.profile.as_ref())
+                    .unwrap_or(0);
+                self.show_profiles = (true, index);
+            }
+            (State::WasSelected(index), State::WasSelected(_), Action::Cancel) => {
+                self.show_pop_up = (false, 0);
+                self.shortcuts.set_state(State::Selected(index));
+            }
+            (State::Selected(mut ts0), State::WasSelected(_), Action::Edit) => {
+                if let Some(index) = ts0.selected()
+                    && let Some(shortcut) = self.shortcuts.get_values().get(index)
+                {
+                    if self.is_from_catalog(shortcut.get_name()) {
+                        let error = from_catalog(shortcut.get_name(), "renamed");
+                        self.report("app.rs - handle_action()", error);
+                        return None;
+                    }
+                    self.save = String::clone(shortcut.get_name());
+                    ts0.select_column(Some(1));
+                    self.shortcuts.set_state(State::Editing(
+                        ts0,
+                        Input::with_value(Input::default(), String::clone(shortcut.get_name())),
+                    ));
+                }
+            }
+            (State::WasSelected(ts0), State::Selected(mut ts1), Action::Edit) => {
                 if let (Some(index0), Some(index1)) = (ts0.selected(), ts1.selected()) {
                     match (
                         self.shortcuts.get_values().get(index0),
                         self.configurations.get_values().get(index1),
                     ) {
+                        (Some(shortcut), Some(configuration))
+                            if shortcut.get_kind() == "Custom" =>
+                        {
+                            // The commands can hold several lines
+                            self.editor = Some(Editor::from(
+                                configuration.get_kind(),
+                                configuration.get_value(),
+                            ));
+                        }
                         (Some(shortcut), Some(configuration)) => {
                             self.save = String::clone(shortcut.get_name());
                             ts1.select_column(Some(1));
@@ -338,7 +1219,19 @@ impl App {
                             ));
                         }
                         (Some(shortcut), None) => {
-                            if let Some(configuration) = self.configurations.get_values().first() {
+                            if let Some(configuration) = self.configurations.get_values().first()
+                                && shortcut.get_kind() == "Custom"
+                            {
+                                ts1.select_column(Some(1));
+                                ts1.select(Some(0));
+                                self.editor = Some(Editor::from(
+                                    configuration.get_kind(),
+                                    configuration.get_value(),
+                                ));
+                                self.configurations.set_state(State::Selected(ts1));
+                            } else if let Some(configuration) =
+                                self.configurations.get_values().first()
+                            {
                                 self.save = String::clone(shortcut.get_name());
                                 ts1.select_column(Some(1));
                                 ts1.select(Some(0));
@@ -358,65 +1251,488 @@ impl App {
             (
                 State::Selected(_) | State::WasSelected(_),
                 State::Selected(_) | State::WasSelected(_),
-                KeyCode::Char('h') | KeyCode::Char('H'),
+                Action::Hide,
             ) => self.configurations.hidde(),
             (
-                State::Selected(_),
-                State::WasSelected(_),
-                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc,
-            ) => self.exit(),
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Theme,
+            ) => self.themes.1 = (self.themes.1 + 1) % self.themes.0.len(),
             (
-                State::WasSelected(_),
-                State::Selected(_),
-                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc,
-            ) => self.exit(),
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::External,
+            ) => self.external = true,
+            (
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Compact,
+            ) => {
+                self.shortcuts.compact();
+                self.configurations.compact();
+            }
+            (
+                State::Selected(_) | State::WasSelected(_),
+                State::Selected(_) | State::WasSelected(_),
+                Action::Help,
+            ) => self.show_help = !self.show_help,
+            (State::Selected(_), State::WasSelected(_), Action::Quit) => self.exit(),
+            (State::WasSelected(_), State::Selected(_), Action::Quit) => self.exit(),
             (_, _, _) => {}
         }
         None
     }
 
+    fn handle_trash_action(&mut self, action: Action) {
+        let len = self.trash.len();
+        match action {
+            Action::Up if len > 0 => {
+                self.show_trash.1 = self.show_trash.1.checked_sub(1).unwrap_or(len - 1);
+            }
+            Action::Down if len > 0 => {
+                self.show_trash.1 = (self.show_trash.1 + 1) % len;
+            }
+            Action::Restore => {
+                if let Some(entry) = self.trash.get(self.show_trash.1) {
+                    let current_names = self
+                        .shortcuts
+                        .get_values()
+                        .iter()
+                        .map(|s| String::clone(s.get_name()))
+                        .collect::<Vec<String>>();
+                    match restore_from_trash(entry, &current_names) {
+                        Ok(name) => self.notify(format!("Restored '{}'", name)),
+                        Err(error) => self.report("app.rs - handle_trash_action()", error),
+                    }
+                    self.show_trash.1 = self.show_trash.1.saturating_sub(1);
+                }
+            }
+            Action::Purge => {
+                if let Some(entry) = self.trash.get(self.show_trash.1) {
+                    self.confirmation = Some((
+                        Pending::PurgeTrash(Some(entry.get_id())),
+                        format!(
+                            "Permanently delete '{}' ({}) ? This cannot be undone.",
+                            entry.get_name(),
+                            entry.get_kind()
+                        ),
+                    ));
+                }
+            }
+            Action::PurgeAll if len > 0 => {
+                self.confirmation = Some((
+                    Pending::PurgeTrash(None),
+                    format!(
+                        "Permanently delete the {} shortcut(s) of the trash ? This cannot be undone.",
+                        len
+                    ),
+                ));
+            }
+            Action::Close => {
+                self.show_trash = (false, 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_workspaces_action(&mut self, action: Action) -> Option<String> {
+        let len = self.workspaces.len();
+        match action {
+            Action::Up if len > 0 => {
+                self.show_workspaces.1 = self.show_workspaces.1.checked_sub(1).unwrap_or(len - 1);
+            }
+            Action::Down if len > 0 => {
+                self.show_workspaces.1 = (self.show_workspaces.1 + 1) % len;
+            }
+            Action::Open => {
+                if let Some(workspace) = self.workspaces.get(self.show_workspaces.1).cloned() {
+                    return self.open_workspace(&workspace);
+                }
+            }
+            Action::Add => self.add_workspace(),
+            Action::Edit if len > 0 => self.external = true,
+            Action::Remove => {
+                if let Some(workspace) = self.workspaces.get(self.show_workspaces.1) {
+                    self.confirmation = Some((
+                        Pending::DeleteWorkspace(String::clone(workspace.get_name())),
+                        format!(
+                            "Delete the workspace '{}' ? Its shortcuts are kept.",
+                            workspace.get_name()
+                        ),
+                    ));
+                }
+            }
+            Action::Close => {
+                self.show_workspaces = (false, 0);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_history_action(&mut self, action: Action) {
+        let len = self.history.len();
+        match action {
+            Action::Up if len > 0 => {
+                self.show_history.1 = self.show_history.1.checked_sub(1).unwrap_or(len - 1);
+            }
+            Action::Down if len > 0 => {
+                self.show_history.1 = (self.show_history.1 + 1) % len;
+            }
+            Action::Close => {
+                self.show_history = (false, 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_profiles_action(&mut self, action: Action) {
+        let len = self.profiles.len();
+        let Some(profile) = self.profiles.get(self.show_profiles.1).cloned() else {
+            self.show_profiles = (false, 0);
+            return;
+        };
+        match action {
+            Action::Up => {
+                self.show_profiles.1 = self.show_profiles.1.checked_sub(1).unwrap_or(len - 1);
+            }
+            Action::Down => {
+                self.show_profiles.1 = (self.show_profiles.1 + 1) % len;
+            }
+            Action::Select => self.switch_profile(&profile),
+            Action::Copy | Action::Move => {
+                let names = self.get_marked_or_selected();
+                let remove = action == Action::Move;
+                // Every profile already shows the catalog, only its overrides are local
+                if let Some(name) = names.iter().find(|name| self.is_from_catalog(name)) {
+                    let error = from_catalog(name, if remove { "moved" } else { "copied" });
+                    self.report("app.rs - handle_profiles_action()", error);
+                    return;
+                }
+                match copy_to_profile(&names, &profile, remove) {
+                    Ok(copied) => {
+                        self.shortcuts.clear_marks();
+                        self.notify(format!(
+                            "{} {} shortcut{} to '{}'",
+                            if remove { "Moved" } else { "Copied" },
+                            copied.len(),
+                            if copied.len() == 1 { "" } else { "s" },
+                            profile
+                        ));
+                    }
+                    Err(error) => self.report("app.rs - handle_profiles_action()", error),
+                }
+            }
+            Action::Close => {
+                self.show_profiles = (false, 0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Use the database of the profile, the view starts again from the first shortcut.
+    fn switch_profile(&mut self, profile: &str) {
+        match get_profile_path(profile).and_then(|path| Database::migrate(&path).map(|_| path)) {
+            Ok(path) => {
+                set_database_path(path);
+                self.profile = Some(String::from(profile));
+                self.shortcuts.set_profile(Some(profile));
+                self.shortcuts.clear_marks();
+                self.shortcuts.set_state(State::Selected(
+                    TableState::new().with_selected(0).with_selected_column(1),
+                ));
+                self.configurations
+                    .set_state(State::WasSelected(TableState::new().with_selected(0)));
+                self.show_profiles = (false, 0);
+                self.notify(format!("Switched to the profile '{}'", profile));
+            }
+            Err(error) => self.report("app.rs - switch_profile()", error),
+        }
+    }
+
+    /// Get the marked shortcuts, or the selected one when none is marked.
+    fn get_marked_or_selected(&self) -> Vec<String> {
+        // The marked shortcuts may have been renamed or deleted since
+        let mut shortcuts = self
+            .shortcuts
+            .get_marked()
+            .iter()
+            .filter(|name| {
+                self.shortcuts
+                    .get_values()
+                    .iter()
+                    .any(|s| s.get_name() == *name)
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+        if shortcuts.is_empty()
+            && let Some(shortcut) = self.get_selected_shortcut()
+        {
+            shortcuts.push(String::clone(shortcut.get_name()));
+        }
+        shortcuts
+    }
+
+    /// Save the marked shortcuts, or the selected one, in a new workspace.
+    fn add_workspace(&mut self) {
+        let shortcuts = self.get_marked_or_selected();
+        let mut index = 1;
+        let name = loop {
+            let name = format!("Workspace{}", index);
+            if !self.workspaces.iter().any(|w| *w.get_name() == name) {
+                break name;
+            }
+            index += 1;
+        };
+        let panes = shortcuts
+            .iter()
+            .map(|shortcut| Pane::from(shortcut, DEFAULT_WINDOW))
+            .collect();
+        match save_workspace(&Workspace::from(&name, DEFAULT_LAYOUT, panes), None) {
+            Ok(_) => {
+                self.shortcuts.clear_marks();
+                self.notify(format!("Added the workspace '{}'", name));
+            }
+            Err(error) => self.report("app.rs - add_workspace()", error),
+        }
+    }
+
+    /// Open every shortcut of the workspace in a pane of its tmux session.<br>
+    /// Outside tmux the app quits and the shell attaches the session.
+    fn open_workspace(&mut self, workspace: &Workspace) -> Option<String> {
+        let mode = format!("Workspace '{}'", workspace.get_name());
+        let mut commands = Vec::new();
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for pane in workspace.get_panes() {
+            let command = self
+                .shortcuts
+                .get_values()
+                .iter()
+                .find(|s| s.get_name() == pane.get_shortcut())
+                .ok_or(Error::Validation(String::from("It doesn't exist anymore.")))
+                .and_then(|shortcut| {
+                    let configurations = load_configurations(shortcut, &self.catalog)?;
+                    let command = build_command(shortcut.get_kind(), &configurations)?;
+                    let rendered = self.shell.render(&command);
+                    entries.push(HistoryEntry::from(
+                        shortcut.get_name(),
+                        shortcut.get_kind(),
+                        &mode,
+                        &self
+                            .shell
+                            .render(&command.mask(&get_secrets(&configurations))),
+                    ));
+                    Ok(rendered)
+                });
+            match command {
+                Ok(command) => commands.push(self.shell.invocation(&command)),
+                Err(error) => errors.push(format!("'{}' : {}", pane.get_shortcut(), error)),
+            }
+        }
+        if !errors.is_empty() {
+            self.alert = Some(format!(
+                "'{}' can't be opened. {}",
+                workspace.get_name(),
+                errors.join(" ")
+            ));
+            return None;
+        }
+
+        let opened = open_workspace(workspace, &commands);
+        if opened.is_ok() {
+            entries.iter().for_each(record);
+        }
+        match opened {
+            Ok(Some(session)) => {
+                let command = format!("tmux attach-session -t {}", self.shell.quote(&session));
+                self.opened = Some(HistoryEntry::from(
+                    workspace.get_name(),
+                    "Workspace",
+                    OpenMode::Current.get_name(),
+                    &command,
+                ));
+                self.exit();
+                Some(command)
+            }
+            Ok(None) => {
+                self.notify(format!("Opened the workspace '{}'", workspace.get_name()));
+                None
+            }
+            Err(error) => {
+                Logs::error("app.rs - open_workspace()", &error);
+                self.alert = Some(format!(
+                    "'{}' can't be opened. {}",
+                    workspace.get_name(),
+                    error
+                ));
+                None
+            }
+        }
+    }
+
+    /// Handle the menu choosing where the shortcut is opened.
+    fn handle_open_action(&mut self, action: Action) -> Option<String> {
+        let (mut entry, command, index) = self.open_menu.take()?;
+        let len = self.open_modes.len();
+        match action {
+            Action::Up => {
+                self.open_menu = Some((entry, command, index.checked_sub(1).unwrap_or(len - 1)))
+            }
+            Action::Down => self.open_menu = Some((entry, command, (index + 1) % len)),
+            Action::Select => match self.open_modes.get(index) {
+                Some(OpenMode::Current) | None => {
+                    self.opened = Some(entry);
+                    self.exit();
+                    return Some(self.shell.render(&command));
+                }
+                Some(mode) => {
+                    let name = String::clone(entry.get_name());
+                    let args = self.shell.invocation(&self.shell.render(&command));
+                    match mode.launch(&name, &args, &self.settings) {
+                        Ok(()) => {
+                            entry.set_mode(mode.get_name());
+                            record(&entry);
+                            self.notify(format!("Opened '{}' in {}", name, mode.get_name()));
+                        }
+                        Err(error) => {
+                            Logs::error("app.rs - handle_open_action()", &error);
+                            self.alert = Some(format!("'{}' can't be opened. {}", name, error));
+                        }
+                    }
+                }
+            },
+            Action::Cancel => {}
+            _ => self.open_menu = Some((entry, command, index)),
+        }
+        None
+    }
+
+    /// Execute the destructive action once it was confirmed.
+    fn confirm(&mut self, pending: Pending) {
+        match pending {
+            Pending::DeleteShortcut(name) => {
+                if let Err(error) = move_to_trash(&name) {
+                    self.report("app.rs - confirm() -1st", error);
+                    return;
+                }
+                self.notify(format!("Moved '{}' to the trash", name));
+                if self.shortcuts.get_values().len() == 1
+                    && let Err(error) = Database::query_write(
+                        "insert into shortcuts values ('Default0', 'echo Welcome on MyShortcuts !', 'Custom');",
+                    )
+                {
+                    self.report("app.rs - confirm() -2nd", error);
+                }
+                if let State::Selected(mut ts0) = self.shortcuts.get_state() {
+                    ts0.select(Some(ts0.selected().unwrap_or(0).saturating_sub(1)));
+                    self.shortcuts.set_state(State::Selected(ts0));
+                }
+            }
+            Pending::RevertShortcut(name) => match revert_to_catalog(&name) {
+                Ok(()) => self.notify(format!("Reverted '{}' to the catalog", name)),
+                Err(error) => self.report("app.rs - confirm() -6th", error),
+            },
+            Pending::ClearProperty(name, index1) => {
+                let kind = self.get_kind(&name);
+                if let Some(configuration) = self.configurations.get_mut_values().get_mut(index1) {
+                    configuration.clear();
+                    let result = save_configurations(
+                        &name,
+                        &kind,
+                        &format_config!(self.configurations.get_values()),
+                    );
+                    match result {
+                        Ok(()) => self.notify(format!("Cleared the property of '{}'", name)),
+                        Err(error) => self.report("app.rs - confirm() -3rd", error),
+                    }
+                }
+            }
+            Pending::PurgeTrash(id) => {
+                match purge_trash(id) {
+                    Ok(()) if id.is_some() => self.notify(String::from("Purged the shortcut")),
+                    Ok(()) => self.notify(String::from("Emptied the trash")),
+                    Err(error) => self.report("app.rs - confirm() -4th", error),
+                }
+                self.show_trash.1 = self.show_trash.1.saturating_sub(1);
+            }
+            Pending::DeleteWorkspace(name) => {
+                match delete_workspace(&name) {
+                    Ok(()) => self.notify(format!("Deleted the workspace '{}'", name)),
+                    Err(error) => self.report("app.rs - confirm() -5th", error),
+                }
+                self.show_workspaces.1 = self.show_workspaces.1.saturating_sub(1);
+            }
+        }
+    }
+
     fn update_widgets_args(&mut self) {
+        if self.show_trash.0 {
+            match get_trash() {
+                Ok(trash) => self.trash = trash,
+                Err(error) => self.report("app.rs - update_widgets_args() -trash", error),
+            }
+            self.show_trash.1 = self.show_trash.1.min(self.trash.len().saturating_sub(1));
+        }
+        if self.show_workspaces.0 {
+            match get_workspaces() {
+                Ok(workspaces) => self.workspaces = workspaces,
+                Err(error) => self.report("app.rs - update_widgets_args() -workspaces", error),
+            }
+            self.show_workspaces.1 = self
+                .show_workspaces
+                .1
+                .min(self.workspaces.len().saturating_sub(1));
+        }
+        if self.show_history.0 {
+            match get_history(None, None, None, HISTORY_LIMIT) {
+                Ok(history) => self.history = history,
+                Err(error) => self.report("app.rs - update_widgets_args() -history", error),
+            }
+            self.show_history.1 = self
+                .show_history
+                .1
+                .min(self.history.len().saturating_sub(1));
+        }
+
         match self.shortcuts.get_state() {
             State::Editing(ts0, input) => {
-                if let Some(index) = ts0.selected() {
-                    if let Some(shortcut) = self.shortcuts.get_mut_values().get_mut(index) {
-                        shortcut
-                            .get_mut_name()
-                            .clone_from(&String::from(input.value()));
-                        return;
-                    }
+                if let Some(index) = ts0.selected()
+                    && let Some(shortcut) = self.shortcuts.get_mut_values().get_mut(index)
+                {
+                    shortcut
+                        .get_mut_name()
+                        .clone_from(&String::from(input.value()));
+                    return;
                 }
             }
             _ => {
                 if let Ok(shortcuts) =
                     Database::query_read("select name,type from shortcuts order by type;")
                 {
-                    self.shortcuts.set_values(
-                        shortcuts
-                            .split("\n")
-                            .filter(|e| !e.is_empty() && *e != "\n")
-                            .map(|cnx| {
-                                if let Ok(cnx) = Shortcut::parse(cnx) {
-                                    cnx
-                                } else {
-                                    Shortcut::default()
-                                }
-                            })
-                            .collect::<Vec<Shortcut>>(),
-                    );
+                    let mut shortcuts = shortcuts
+                        .split("\n")
+                        .filter(|e| !e.is_empty() && *e != "\n")
+                        .map(|cnx| {
+                            if let Ok(cnx) = Shortcut::parse(cnx) {
+                                cnx
+                            } else {
+                                Shortcut::default()
+                            }
+                        })
+                        .collect::<Vec<Shortcut>>();
+                    self.catalog.merge_shortcuts(&mut shortcuts);
+                    self.shortcuts.set_values(shortcuts);
                 }
             }
         }
 
         match (self.shortcuts.get_state(), self.configurations.get_state()) {
             (State::WasSelected(_), State::Editing(ts1, input)) => {
-                if let Some(index) = ts1.selected() {
-                    if let Some(configuration) = self.configurations.get_mut_values().get_mut(index)
-                    {
-                        configuration
-                            .get_mut_value()
-                            .clone_from(&String::from(input.value()));
-                    }
+                if let Some(index) = ts1.selected()
+                    && let Some(configuration) = self.configurations.get_mut_values().get_mut(index)
+                {
+                    configuration.set_value(input.value());
                 }
             }
             (
@@ -425,35 +1741,66 @@ impl App {
             ) => {
                 let index0 = ts0.selected().unwrap_or(0);
                 if let Some(shortcut) = self.shortcuts.get_values().get(index0) {
-                    match Database::query_read(&format!(
-                        "select configuration from shortcuts where name='{}';",
-                        shortcut.get_name()
-                    )) {
-                        Ok(configurations) => {
-                            let configurations = configurations
-                                .split(";")
-                                .map(|c| c.trim_end())
-                                .collect::<Vec<&str>>();
-                            let new_configurations =
-                                get_current_config(configurations, shortcut.get_kind());
+                    match load_configurations(shortcut, &self.catalog) {
+                        Ok(new_configurations) => {
                             self.configurations.set_values(new_configurations);
                         }
                         Err(error) => {
-                            Logs::write(format!(
-                                "\nERROR : app.rs - update_widgets_args() -1st {}",
-                                error
-                            ));
+                            self.report("app.rs - update_widgets_args() -1st", error);
                         }
                     }
                 } else {
-                    Logs::write(format!(
-                        "\nERROR : app.rs - update_widgets_args() -2nd :\n   app.shortcuts is empty : {:?}",
-                        self.shortcuts.get_values()
-                    ));
+                    Logs::error(
+                        "app.rs - update_widgets_args() -2nd",
+                        format!("app.shortcuts is empty : {:?}", self.shortcuts.get_values()),
+                    );
                 }
             }
             _ => {}
         }
+
+        if let Some(shortcut) = self.get_selected_shortcut() {
+            let rules = get_rules(shortcut.get_kind());
+            self.configurations.validate(rules);
+        }
+    }
+
+    /// Get the shortcut currently selected in the Shortcuts widget.
+    fn get_selected_shortcut(&self) -> Option<&Shortcut> {
+        let ts0 = match self.shortcuts.get_state() {
+            State::Selected(ts) | State::WasSelected(ts) | State::Editing(ts, _) => ts,
+        };
+        self.shortcuts.get_values().get(ts0.selected().unwrap_or(0))
+    }
+
+    /// Check if the shortcut only comes from the catalog, without a local row to change.
+    fn is_from_catalog(&self, name: &str) -> bool {
+        self.catalog.contains(name) && is_local(name).is_ok_and(|local| !local)
+    }
+
+    /// Get the kind of the shortcut named `name`.
+    fn get_kind(&self, name: &str) -> String {
+        self.shortcuts
+            .get_values()
+            .iter()
+            .find(|s| s.get_name() == name)
+            .map(|s| String::clone(s.get_kind()))
+            .unwrap_or_default()
+    }
+
+    /// Log the error and show it in a toast, unless the same error is already shown.
+    fn report(&mut self, origin: &str, error: Error) {
+        let message = error.to_string();
+        if !self.toasts.iter().any(|(m, _)| *m == message) {
+            Logs::error(origin, &error);
+            self.status = Some((String::clone(&message), true, Instant::now()));
+            self.toasts.push((message, Instant::now()));
+        }
+    }
+
+    /// Show the result of an action in the status bar for a few seconds.
+    fn notify(&mut self, message: String) {
+        self.status = Some((message, false, Instant::now()));
     }
 
     fn exit(&mut self) {
@@ -497,38 +1844,12 @@ impl App {
         }
     }
 
-    fn get_shortcut(&self, kind: String) -> String {
-        let current_configuration = self
-            .configurations
-            .get_values()
-            .iter()
-            .map(|c| c.get_value())
-            .collect::<Vec<&String>>();
-        if kind == "Oracle" {
-            oracle(filter_config!(current_configuration))
-        } else if kind == "MySQL" {
-            mysql(filter_config!(current_configuration))
-        } else if kind == "MariaDB" {
-            mariadb(filter_config!(current_configuration))
-        } else if kind == "PostgreSQL" {
-            postgresql(filter_config!(current_configuration))
-        } else if kind == "SQLite" {
-            sqlite(filter_config!(current_configuration))
-        } else if kind == "Redis" {
-            redis(filter_config!(current_configuration))
-        } else if kind == "MongoDB" {
-            mongodb(filter_config!(current_configuration))
-        } else if kind == "Neo4j" {
-            neo4j(filter_config!(current_configuration))
-        } else if kind == "Custom" {
-            String::clone(self.configurations.get_values()[0].get_value())
-        } else {
-            Logs::write(format!("Configuration detected : {:#?}", current_configuration));
-            "".to_owned()
-        }
+    /// Build the command of the shortcut, or list the required properties that are unset.
+    fn get_shortcut(&self, kind: String) -> Result<ShellCommand> {
+        build_command(&kind, self.configurations.get_values())
     }
 
-    fn add_new_shortcut(&self) {
+    fn add_new_shortcut(&mut self) {
         let kind = AVAILABLE_SHEME[self.show_pop_up.1];
         let current_names = self
             .shortcuts
@@ -536,56 +1857,248 @@ impl App {
             .iter()
             .map(|s| String::clone(s.get_name()))
             .collect::<Vec<String>>();
-        let fields: usize = match kind {
-            "Oracle" | "PostgreSQL" | "Neo4j" | "MySQL" | "MariaDB" => 4,
-            "Redis" | "MongoDB" => 2,
-            "SQLite" | "Custom" => 1,
-            _ => 0
-        };
+        let fields: usize = get_rules(kind).len();
         if fields > 0 {
-            insert_default_config(current_names, kind, fields);
-        }
-        else {
-            Logs::write(format!("\nERROR : Invalid Sheme '{}'", kind));
+            match insert_default_config(current_names, kind, fields) {
+                Ok(name) => self.notify(format!("Added '{}'", name)),
+                Err(error) => self.report("app.rs - add_new_shortcut()", error),
+            }
+        } else {
+            Logs::error(
+                "app.rs - add_new_shortcut()",
+                format!("Invalid Sheme '{}'", kind),
+            );
         }
     }
 
     fn save_editing(&mut self, new_value: String, is_shortcut: bool) {
-        let query: String = if is_shortcut {
-            format!(
-                "update shortcuts set name='{}' where name='{}';",
-                new_value, self.save
-            )
+        // The edited row already holds the new name
+        if is_shortcut
+            && self
+                .shortcuts
+                .get_values()
+                .iter()
+                .filter(|s| *s.get_name() == new_value)
+                .count()
+                > 1
+        {
+            self.report(
+                "app.rs - save_editing()",
+                Error::Validation(format!("Rename failed : '{}' already exists", new_value)),
+            );
+            self.save = String::new();
+            return;
+        }
+        if is_shortcut && !is_valid_name(&new_value) {
+            self.report(
+                "app.rs - save_editing()",
+                Error::Validation(String::from(
+                    "Rename failed : the name can't contain ';' or control characters",
+                )),
+            );
+            self.save = String::new();
+            return;
+        }
+        let result = if is_shortcut {
+            Database::query_write(&format!(
+                "update shortcuts set name='{}' where name='{}';
+                update workspace_panes set shortcut='{}' where shortcut='{}';",
+                escape(&new_value),
+                escape(&self.save),
+                escape(&new_value),
+                escape(&self.save)
+            ))
         } else {
-            format!(
-                "update shortcuts set configuration='{}' where name='{}';",
-                format_config!(self.configurations.get_values()),
-                self.save
+            // A new password must not be written in the logs of the query
+            Logs::add_secrets(&self.configurations.get_secrets());
+            save_configurations(
+                &self.save,
+                &self.get_kind(&self.save),
+                &format_config!(self.configurations.get_values()),
             )
         };
-        let _ = Database::query_write(&query);
+        match result {
+            Ok(()) if is_shortcut && new_value != self.save => {
+                self.notify(format!("Renamed '{}' to '{}'", self.save, new_value))
+            }
+            Ok(()) if is_shortcut => {}
+            Ok(()) => self.notify(format!("Saved '{}'", self.save)),
+            Err(error) => self.report("app.rs - save_editing()", error),
+        }
         self.save = String::new();
     }
 }
 
-fn get_current_config(configurations: Vec<&str>, kind: &str) -> Vec<Configuration> {
-    let scheme: Vec<&str> = match kind {
-        "MySQL" | "MariaDB" => SOCKET_SCHEME.to_vec(),
-        "Oracle" | "PostgreSQL" | "Neo4j" => CLASSIC_SHEME.to_vec(),
-        "SQLite" => FILE_SCHEME.to_vec(),
-        "Redis" => REDIS_SCHEME.to_vec(),
-        "MongoDB" => MONGODB_SCHEME.to_vec(),
-        "Custom" => CUSTOM_SHEME.to_vec(),
-        _ => vec!["Unknow"],
+/// Give the terminal back to an external program.
+fn suspend(terminal: &mut Tui) {
+    leave(terminal.backend_mut());
+}
+
+/// Take the terminal back after `suspend`.
+fn resume(terminal: &mut Tui) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()
+}
+
+/// Get where the app is drawn : the standard output or `/dev/tty`.
+fn open_output(tty: bool) -> io::Result<Box<dyn Write>> {
+    if tty {
+        Ok(Box::new(OpenOptions::new().write(true).open("/dev/tty")?))
+    } else {
+        Ok(Box::new(io::stdout()))
+    }
+}
+
+/// Give the terminal back to the shell.
+fn leave(output: &mut impl Write) {
+    if let Err(error) = disable_raw_mode() {
+        Logs::error("app.rs - leave()", error);
+    }
+    let _ = execute!(output, DisableMouseCapture, LeaveAlternateScreen, Show);
+}
+
+/// Get the row selected after moving by a page or to an end of a table.
+fn jump(index: Option<usize>, len: usize, page: usize, action: Action) -> usize {
+    let index = index.unwrap_or(0);
+    let last = len.saturating_sub(1);
+    match action {
+        Action::PageUp => index.saturating_sub(page),
+        Action::PageDown => (index + page).min(last),
+        Action::First => 0,
+        _ => last,
+    }
+}
+
+/// Move the selection of a table with the mouse wheel, without wrapping around.
+fn scroll_state(state: State, len: usize, down: bool) -> State {
+    let scroll = |mut ts: TableState| {
+        let index = ts.selected().unwrap_or(0);
+        let index = if down {
+            (index + 1).min(len.saturating_sub(1))
+        } else {
+            index.saturating_sub(1)
+        };
+        ts.select(Some(index));
+        ts
     };
+    match state {
+        State::Selected(ts) => State::Selected(scroll(ts)),
+        State::WasSelected(ts) => State::WasSelected(scroll(ts)),
+        State::Editing(ts, input) => State::Editing(ts, input),
+    }
+}
+
+/// Build the command of a shortcut, or list the required properties that are unset.
+fn build_command(kind: &str, configurations: &[Configuration]) -> Result<ShellCommand> {
+    let rules = get_rules(kind);
+    let missing = configurations
+        .iter()
+        .zip(rules.iter())
+        .filter(|(configuration, rule)| configuration.is_unset() && rule.is_required())
+        .map(|(configuration, _)| String::clone(configuration.get_kind()))
+        .collect::<Vec<String>>();
+    if !missing.is_empty() {
+        return Err(Error::MissingField(missing));
+    }
+
+    let current_configuration = configurations
+        .iter()
+        .map(|c| c.get_value())
+        .collect::<Vec<&String>>();
+    let command = if kind == "Oracle" {
+        oracle(filter_config!(current_configuration))
+    } else if kind == "MySQL" {
+        mysql(filter_config!(current_configuration))
+    } else if kind == "MariaDB" {
+        mariadb(filter_config!(current_configuration))
+    } else if kind == "PostgreSQL" {
+        postgresql(filter_config!(current_configuration))
+    } else if kind == "SQLite" {
+        sqlite(filter_config!(current_configuration))
+    } else if kind == "Redis" {
+        redis(filter_config!(current_configuration))
+    } else if kind == "MongoDB" {
+        mongodb(filter_config!(current_configuration))
+    } else if kind == "Neo4j" {
+        neo4j(filter_config!(current_configuration))
+    } else if kind == "Custom" {
+        ShellCommand::script(
+            configurations
+                .first()
+                .map(|c| String::clone(c.get_value()))
+                .unwrap_or_default(),
+        )
+    } else {
+        Logs::warn(
+            "app.rs - build_command()",
+            format!("Configuration detected : {:#?}", current_configuration),
+        );
+        ShellCommand::script("".to_owned())
+    };
+    Ok(command)
+}
+
+/// Add the entry to the history, a failure is only logged since the shortcut is opened anyway.
+fn record(entry: &HistoryEntry) {
+    Logs::info(
+        "app.rs - record()",
+        format!(
+            "'{}' opened in {} : {}",
+            entry.get_name(),
+            entry.get_mode(),
+            entry.get_command()
+        ),
+    );
+    if let Err(error) = add_history(entry) {
+        Logs::error("app.rs - record()", error);
+    }
+}
+
+/// The error of an action refused on a shortcut of the catalog, like `renamed`.
+fn from_catalog(name: &str, action: &str) -> Error {
+    Error::Validation(format!(
+        "'{}' comes from the shared catalog, it can't be {}",
+        name, action
+    ))
+}
+
+/// Read the properties of the shortcut from the database, the unset ones inherit the values
+/// of the catalog.
+fn load_configurations(shortcut: &Shortcut, catalog: &Catalog) -> Result<Vec<Configuration>> {
+    let configurations = Database::query_read(&format!(
+        "select configuration from shortcuts where name='{}';",
+        escape(shortcut.get_name())
+    ))?;
+    let configurations = decode_config(
+        configurations
+            .trim_end_matches('\n')
+            .strip_suffix(";")
+            .unwrap_or_default(),
+    );
+    let mut configurations = get_current_config(configurations, shortcut.get_kind());
+    catalog.merge_configurations(shortcut, &mut configurations);
+    Logs::add_secrets(&get_secrets(&configurations));
+    Ok(configurations)
+}
+
+fn get_current_config(configurations: Vec<Option<String>>, kind: &str) -> Vec<Configuration> {
+    let mut scheme = get_scheme(kind);
+    if scheme.is_empty() {
+        scheme.push("Unknow");
+    }
     scheme
         .iter()
         .enumerate()
         .map(|(index, kind)| {
-            if let Some(value) = configurations.get(index) {
+            if let Some(Some(value)) = configurations.get(index) {
                 Configuration::from(value, kind)
             } else {
-                Configuration::from("", kind)
+                Configuration::unset(kind)
             }
         })
         .collect()
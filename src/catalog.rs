@@ -0,0 +1,302 @@
+use std::fs;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::database::{SECRET_FIELDS, get_rules, get_scheme};
+use crate::error::{Error, Result};
+use crate::objects::{Configuration, Shortcut};
+
+/// A shortcut of the catalog with the value of each property of its scheme,
+/// `None` for the ones it doesn't give.
+#[derive(Debug)]
+struct CatalogEntry {
+    shortcut: Shortcut,
+    values: Vec<Option<String>>,
+}
+
+/// The shortcuts shared by a team in a read-only TOML file, without their secrets :
+/// ```toml
+/// [[shortcuts]]
+/// name = "prod-pg"
+/// kind = "PostgreSQL"
+///
+/// [shortcuts.properties]
+/// Host = "db.prod.example.com"
+/// Port = "5432"
+/// ```
+/// They are shown under the local shortcuts. A local shortcut with the same name and kind
+/// overrides their values one by one, the properties it leaves unset inherit them.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    pub fn load(path: &Path) -> Result<Catalog> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            Error::io(
+                format!("Failed to read the catalog '{}'", path.display()),
+                e,
+            )
+        })?;
+        Catalog::parse(&content)
+            .map_err(|e| Error::Parse(format!("Invalid catalog '{}' : {}", path.display(), e)))
+    }
+
+    pub fn parse(content: &str) -> Result<Catalog> {
+        let table = content.parse::<Table>()?;
+        if let Some(key) = table.keys().find(|k| *k != "shortcuts") {
+            return Err(Error::Parse(format!("Unknown key '{}'", key)));
+        }
+        let shortcuts = match table.get("shortcuts") {
+            Some(Value::Array(shortcuts)) => shortcuts.clone(),
+            Some(_) => {
+                return Err(Error::Parse(String::from(
+                    "'shortcuts' must be an array of tables",
+                )));
+            }
+            None => vec![],
+        };
+
+        let mut entries: Vec<CatalogEntry> = Vec::new();
+        for shortcut in &shortcuts {
+            let entry = parse_entry(shortcut)?;
+            if entries
+                .iter()
+                .any(|e| e.shortcut.get_name() == entry.shortcut.get_name())
+            {
+                return Err(Error::Validation(format!(
+                    "The shortcut '{}' is defined twice",
+                    entry.shortcut.get_name()
+                )));
+            }
+            entries.push(entry);
+        }
+        Ok(Catalog { entries })
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.shortcut.get_name() == name)
+    }
+
+    /// Check if the local shortcut overrides one of the catalog, with the same name and kind.
+    pub fn overrides(&self, shortcut: &Shortcut) -> bool {
+        self.entries.iter().any(|e| {
+            e.shortcut.get_name() == shortcut.get_name()
+                && e.shortcut.get_kind() == shortcut.get_kind()
+        })
+    }
+
+    /// Add the shortcuts of the catalog missing from the local ones, sorted by kind like them.
+    pub fn merge_shortcuts(&self, shortcuts: &mut Vec<Shortcut>) {
+        for entry in &self.entries {
+            if !shortcuts
+                .iter()
+                .any(|s| s.get_name() == entry.shortcut.get_name())
+            {
+                shortcuts.push(entry.shortcut.clone());
+            }
+        }
+        shortcuts.sort_by(|a, b| a.get_kind().cmp(b.get_kind()));
+    }
+
+    /// Fill the unset properties of the shortcut with the values of the catalog.<br>
+    /// Nothing is inherited when the local shortcut has another kind.
+    pub fn merge_configurations(&self, shortcut: &Shortcut, configurations: &mut [Configuration]) {
+        let Some(entry) = self.entries.iter().find(|e| {
+            e.shortcut.get_name() == shortcut.get_name()
+                && e.shortcut.get_kind() == shortcut.get_kind()
+        }) else {
+            return;
+        };
+        for (configuration, value) in configurations.iter_mut().zip(entry.values.iter()) {
+            if let Some(value) = value
+                && configuration.is_unset()
+            {
+                configuration.inherit(value);
+            }
+        }
+    }
+}
+
+fn parse_entry(value: &Value) -> Result<CatalogEntry> {
+    let table = value
+        .as_table()
+        .ok_or(Error::Parse(String::from("Each shortcut must be a table")))?;
+    let name = table
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(Error::Validation(String::from(
+            "Each shortcut needs a name",
+        )))?;
+    if let Some(key) = table
+        .keys()
+        .find(|k| !["name", "kind", "properties"].contains(&k.as_str()))
+    {
+        return Err(Error::Parse(format!("'{}' : Unknown key '{}'", name, key)));
+    }
+    let kind = table
+        .get("kind")
+        .and_then(|k| k.as_str())
+        .unwrap_or_default();
+    let scheme = get_scheme(kind);
+    if scheme.is_empty() {
+        return Err(Error::Validation(format!(
+            "'{}' : Unknown kind '{}'",
+            name, kind
+        )));
+    }
+
+    let properties = match table.get("properties") {
+        Some(Value::Table(properties)) => properties.clone(),
+        Some(_) => {
+            return Err(Error::Parse(format!(
+                "'{}' : 'properties' must be a table",
+                name
+            )));
+        }
+        None => Table::new(),
+    };
+    if let Some(key) = properties.keys().find(|k| !scheme.contains(&k.as_str())) {
+        return Err(Error::Parse(format!(
+            "'{}' : Unknown property '{}' for {}",
+            name, key, kind
+        )));
+    }
+    if let Some(key) = properties
+        .keys()
+        .find(|k| SECRET_FIELDS.contains(&k.as_str()))
+    {
+        return Err(Error::Validation(format!(
+            "'{}' : The catalog is shared, it can't hold the '{}'",
+            name, key
+        )));
+    }
+
+    let rules = get_rules(kind);
+    let mut values = Vec::new();
+    for (index, property) in scheme.iter().enumerate() {
+        let value = match properties.get(*property) {
+            None => None,
+            Some(Value::String(value)) => Some(String::clone(value)),
+            Some(Value::Integer(value)) => Some(value.to_string()),
+            Some(_) => {
+                return Err(Error::Parse(format!(
+                    "'{}' : {} must be a string",
+                    name, property
+                )));
+            }
+        };
+        if let Some(value) = &value
+            && let Some(Err(error)) = rules.get(index).map(|r| r.validate(value))
+        {
+            return Err(Error::Validation(format!(
+                "'{}' : {} : {}",
+                name, property, error
+            )));
+        }
+        values.push(value);
+    }
+    Ok(CatalogEntry {
+        shortcut: Shortcut::from(name, kind),
+        values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"
+        [[shortcuts]]
+        name = "prod-pg"
+        kind = "PostgreSQL"
+
+        [shortcuts.properties]
+        Host = "db.prod.example.com"
+        Port = 5432
+    "#;
+
+    fn unset_configurations(kind: &str) -> Vec<Configuration> {
+        get_scheme(kind)
+            .into_iter()
+            .map(Configuration::unset)
+            .collect()
+    }
+
+    fn get_values(configurations: &[Configuration]) -> Vec<(&str, bool)> {
+        configurations
+            .iter()
+            .take(3)
+            .map(|c| (c.get_value().as_str(), c.is_inherited()))
+            .collect()
+    }
+
+    #[test]
+    fn merge_the_missing_shortcuts() {
+        let catalog = Catalog::parse(CATALOG).unwrap();
+        let mut shortcuts = vec![Shortcut::from("notes", "Custom")];
+        catalog.merge_shortcuts(&mut shortcuts);
+        assert_eq!(
+            shortcuts
+                .iter()
+                .map(|s| s.get_name().as_str())
+                .collect::<Vec<_>>(),
+            ["notes", "prod-pg"]
+        );
+
+        // The local shortcut takes the place of the one of the catalog
+        let mut shortcuts = vec![Shortcut::from("prod-pg", "MySQL")];
+        catalog.merge_shortcuts(&mut shortcuts);
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].get_kind(), "MySQL");
+    }
+
+    #[test]
+    fn override_the_values_one_by_one() {
+        let catalog = Catalog::parse(CATALOG).unwrap();
+        let shortcut = Shortcut::from("prod-pg", "PostgreSQL");
+        assert!(catalog.overrides(&shortcut));
+
+        let mut configurations = unset_configurations("PostgreSQL");
+        configurations[1].set_value("6432");
+        configurations[2].set_value("me");
+        catalog.merge_configurations(&shortcut, &mut configurations);
+        assert_eq!(
+            get_values(&configurations),
+            [
+                ("db.prod.example.com", true),
+                ("6432", false),
+                ("me", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn revert_to_the_values_of_the_catalog() {
+        let catalog = Catalog::parse(CATALOG).unwrap();
+        let shortcut = Shortcut::from("prod-pg", "PostgreSQL");
+
+        // Without its local row the shortcut is back with every property unset
+        let mut configurations = unset_configurations("PostgreSQL");
+        catalog.merge_configurations(&shortcut, &mut configurations);
+        assert_eq!(
+            get_values(&configurations),
+            [("db.prod.example.com", true), ("5432", true), ("", false)]
+        );
+    }
+
+    #[test]
+    fn nothing_is_inherited_by_another_kind() {
+        let catalog = Catalog::parse(CATALOG).unwrap();
+        let shortcut = Shortcut::from("prod-pg", "Neo4j");
+        assert!(!catalog.overrides(&shortcut));
+
+        let mut configurations = unset_configurations("Neo4j");
+        catalog.merge_configurations(&shortcut, &mut configurations);
+        assert!(configurations.iter().all(|c| !c.is_inherited()));
+    }
+}
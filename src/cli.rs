@@ -0,0 +1,324 @@
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use crate::error::{Error, Result};
+use crate::shell::Shell;
+use crate::utils::parse_date;
+
+pub const USAGE: &str = "\
+Usage :
+    myshortcuts [--shell <shell>] [--output <file>]    Choose a shortcut, its command is written for <shell> in <file>
+    myshortcuts [--shell <shell>] --print              Choose a shortcut, its command is printed on the standard output
+    myshortcuts [--shell <shell>] --exec               Choose a shortcut, its command is run by myshortcuts
+    myshortcuts init <shell>                           Print the functions to add to the configuration of <shell>
+    myshortcuts history [--shortcut <name>] [--since <date>] [--until <date>] [--limit <n>]
+                                                       Print the opened shortcuts, the most recent first
+    myshortcuts --help                                 Print this message
+
+Every mode but init accepts --db <file> to use another database, it's also read from $MYSHORTCUTS_DB,
+or --profile <name> to use the database of a profile, which is created the first time.
+
+Shells : bash, zsh, fish, nu, powershell
+The file is also read from $MYSHORTCUTS_OUTPUT, it defaults to $XDG_RUNTIME_DIR/myshortcuts_command.<pid>.<extension>
+and it must not exist. It's removed when no command is chosen.
+The modes choosing a shortcut accept --verbose to write the debug messages in the log file, otherwise
+the level of the logs (error, warn, info or debug) is read from $MYSHORTCUTS_LOG and it defaults to warn.
+The dates of the history are written YYYY-MM-DD and both bounds are included. The days start and end
+at midnight UTC, not in the local time zone, and the dates printed are in UTC too.";
+
+/// The variable read when `--output` isn't given.
+const OUTPUT_VARIABLE: &str = "MYSHORTCUTS_OUTPUT";
+/// The variable read when `--db` isn't given.
+const DATABASE_VARIABLE: &str = "MYSHORTCUTS_DB";
+/// Number of entries printed by `history` without `--limit`.
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// What the user asked on the command line.
+#[derive(Debug)]
+pub enum Command {
+    /// Run the TUI
+    Run(Options),
+    /// Print the wrapper function of the shell
+    Init(Shell),
+    /// Print the history
+    History(HistoryFilter),
+    Help,
+}
+
+/// The entries of the history to print.
+#[derive(Debug)]
+pub struct HistoryFilter {
+    shortcut: Option<String>,
+    /// Unix timestamp of the first day
+    since: Option<i64>,
+    /// Unix timestamp of the day after the last one
+    until: Option<i64>,
+    limit: usize,
+    database: Option<PathBuf>,
+    profile: Option<String>,
+}
+
+impl HistoryFilter {
+    pub fn get_shortcut(&self) -> Option<&str> {
+        self.shortcut.as_deref()
+    }
+
+    pub fn get_since(&self) -> Option<i64> {
+        self.since
+    }
+
+    pub fn get_until(&self) -> Option<i64> {
+        self.until
+    }
+
+    pub fn get_limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn get_database(&self) -> Option<PathBuf> {
+        database_or_variable(&self.database, &self.profile)
+    }
+
+    pub fn get_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+}
+
+/// The options of the TUI.
+#[derive(Debug)]
+pub struct Options {
+    shell: Shell,
+    /// The file where the command is written
+    output: Option<PathBuf>,
+    /// Print the command instead of writing it in a file
+    print: bool,
+    /// Run the command instead of writing it in a file
+    exec: bool,
+    /// Write the debug messages in the log file
+    verbose: bool,
+    /// The database used instead of the one of the data folder
+    database: Option<PathBuf>,
+    /// The profile whose database is used
+    profile: Option<String>,
+}
+
+impl Options {
+    pub fn get_shell(&self) -> Shell {
+        self.shell
+    }
+
+    pub fn is_print(&self) -> bool {
+        self.print
+    }
+
+    pub fn is_exec(&self) -> bool {
+        self.exec
+    }
+
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Get the database chosen by `--db` or `$MYSHORTCUTS_DB`, if any.<br>
+    /// `--profile` is preferred to the variable.
+    pub fn get_database(&self) -> Option<PathBuf> {
+        database_or_variable(&self.database, &self.profile)
+    }
+
+    pub fn get_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Get the file where the command is written : `--output`, `$MYSHORTCUTS_OUTPUT`
+    /// or a file named after the process in `$XDG_RUNTIME_DIR` (the temporary folder
+    /// when it isn't set), so two runs or two users never share it.
+    pub fn get_output(&self) -> PathBuf {
+        if let Some(output) = &self.output {
+            return output.clone();
+        }
+        if let Some(output) = env::var_os(OUTPUT_VARIABLE).filter(|o| !o.is_empty()) {
+            return PathBuf::from(output);
+        }
+        let mut path = env::var_os("XDG_RUNTIME_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or(env::temp_dir());
+        path.push(format!(
+            "myshortcuts_command.{}.{}",
+            process::id(),
+            self.shell.get_extension()
+        ));
+        path
+    }
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command> {
+    let mut options = Options {
+        shell: Shell::detect(),
+        output: None,
+        print: false,
+        exec: false,
+        verbose: false,
+        database: None,
+        profile: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "init" => {
+                let shell = args.next().ok_or(Error::Parse(String::from(
+                    "'init' expects the name of a shell",
+                )))?;
+                return Shell::parse(&shell).map(Command::Init);
+            }
+            "history" => {
+                return parse_history_args(args, options.database, options.profile)
+                    .map(Command::History);
+            }
+            "--shell" => {
+                let shell = args.next().ok_or(Error::Parse(String::from(
+                    "'--shell' expects the name of a shell",
+                )))?;
+                options.shell = Shell::parse(&shell)?;
+            }
+            "--output" => {
+                let output = args.next().ok_or(Error::Parse(String::from(
+                    "'--output' expects the path of a file",
+                )))?;
+                options.output = Some(PathBuf::from(output));
+            }
+            "--db" => options.database = Some(parse_database(args.next())?),
+            "--profile" => options.profile = Some(parse_profile(args.next())?),
+            "--print" => options.print = true,
+            "--exec" => options.exec = true,
+            "-v" | "--verbose" => options.verbose = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(Error::Parse(format!("Unknown argument '{}'", arg))),
+        }
+    }
+    if options.print && options.exec {
+        return Err(Error::Parse(String::from(
+            "'--print' and '--exec' can't be used together",
+        )));
+    }
+    check_database(&options.database, &options.profile)?;
+    Ok(Command::Run(options))
+}
+
+/// Parse the arguments after `history`, `database` and `profile` were given before it.
+fn parse_history_args(
+    mut args: impl Iterator<Item = String>,
+    database: Option<PathBuf>,
+    profile: Option<String>,
+) -> Result<HistoryFilter> {
+    let mut filter = HistoryFilter {
+        shortcut: None,
+        since: None,
+        until: None,
+        limit: DEFAULT_HISTORY_LIMIT,
+        database,
+        profile,
+    };
+
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--db" => {
+                filter.database = Some(parse_database(args.next())?);
+                continue;
+            }
+            "--profile" => {
+                filter.profile = Some(parse_profile(args.next())?);
+                continue;
+            }
+            "--shortcut" | "--since" | "--until" | "--limit" => args
+                .next()
+                .ok_or(Error::Parse(format!("'{}' expects a value", arg)))?,
+            _ => return Err(Error::Parse(format!("Unknown argument '{}'", arg))),
+        };
+        match arg.as_str() {
+            "--shortcut" => filter.shortcut = Some(value),
+            "--since" => filter.since = Some(parse_date(&value)?),
+            "--until" => filter.until = Some(parse_date(&value)? + 86400),
+            "--limit" => {
+                filter.limit = value.parse::<usize>().map_err(|_| {
+                    Error::Parse(format!("'--limit' expects a number, not '{}'", value))
+                })?
+            }
+            _ => {}
+        }
+    }
+    check_database(&filter.database, &filter.profile)?;
+    Ok(filter)
+}
+
+fn parse_database(value: Option<String>) -> Result<PathBuf> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .ok_or(Error::Parse(String::from(
+            "'--db' expects the path of a file",
+        )))
+}
+
+fn parse_profile(value: Option<String>) -> Result<String> {
+    value
+        .filter(|v| !v.is_empty())
+        .ok_or(Error::Parse(String::from(
+            "'--profile' expects the name of a profile",
+        )))
+}
+
+fn check_database(database: &Option<PathBuf>, profile: &Option<String>) -> Result<()> {
+    if database.is_some() && profile.is_some() {
+        return Err(Error::Parse(String::from(
+            "'--db' and '--profile' can't be used together",
+        )));
+    }
+    Ok(())
+}
+
+fn database_or_variable(database: &Option<PathBuf>, profile: &Option<String>) -> Option<PathBuf> {
+    if profile.is_some() {
+        return None;
+    }
+    database.clone().or(env::var_os(DATABASE_VARIABLE)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(args: &[&str]) -> HistoryFilter {
+        let args = ["history"].iter().chain(args).map(|a| String::from(*a));
+        match parse_args(args) {
+            Ok(Command::History(filter)) => filter,
+            other => panic!("Expected the history, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn until_includes_the_whole_day() {
+        let filter = history(&["--since", "2026-01-31", "--until", "2026-01-31"]);
+        let since = filter.get_since().unwrap();
+        let until = filter.get_until().unwrap();
+        assert_eq!(since, parse_date("2026-01-31").unwrap());
+        // The entries are kept while `opened_at < until`
+        assert_eq!(until, parse_date("2026-02-01").unwrap());
+        assert_eq!(until - since, 86400);
+    }
+
+    #[test]
+    fn until_the_last_day_of_the_year() {
+        let filter = history(&["--until", "2026-12-31"]);
+        assert_eq!(filter.get_until(), parse_date("2027-01-01").ok());
+    }
+
+    #[test]
+    fn invalid_dates_are_refused() {
+        let args = ["history", "--until", "2026-02-29"].map(String::from);
+        assert!(parse_args(args.into_iter()).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::PathBuf;
+
+use toml::Table;
+
+use crate::logs::Logs;
+use crate::utils::{expand_home, get_config_path};
+
+pub const CONFIG_NAME: &str = "config.toml";
+
+/// User settings read from `config.toml` in the configuration folder.<br>
+/// Every missing key keeps its default value.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Number of days before a deleted shortcut is purged from the trash
+    trash_retention_days: u64,
+    /// Command opening a terminal emulator, like `alacritty -e {command}`
+    terminal: Option<String>,
+    /// The shared catalog merged under the local shortcuts
+    catalog: Option<PathBuf>,
+}
+
+impl Settings {
+    pub fn default() -> Self {
+        Settings {
+            trash_retention_days: 30,
+            terminal: None,
+            catalog: None,
+        }
+    }
+
+    pub fn load() -> Self {
+        let mut settings = Settings::default();
+        let Ok(mut path) = get_config_path() else {
+            return settings;
+        };
+        path.push(CONFIG_NAME);
+
+        let Ok(content) = fs::read_to_string(&path) else {
+            return settings;
+        };
+        match content.parse::<Table>() {
+            Ok(table) => {
+                if let Some(days) = table
+                    .get("trash_retention_days")
+                    .and_then(|v| v.as_integer())
+                {
+                    settings.trash_retention_days = days.max(0) as u64;
+                }
+                if let Some(terminal) = table
+                    .get("terminal")
+                    .and_then(|v| v.as_str())
+                    .filter(|t| !t.trim().is_empty())
+                {
+                    settings.terminal = Some(String::from(terminal));
+                }
+                if let Some(catalog) = table
+                    .get("catalog")
+                    .and_then(|v| v.as_str())
+                    .filter(|c| !c.trim().is_empty())
+                {
+                    settings.catalog = Some(expand_home(catalog.trim()));
+                }
+            }
+            Err(error) => Logs::error("config.rs - Settings::load()", error),
+        }
+        settings
+    }
+
+    pub fn get_trash_retention_days(&self) -> u64 {
+        self.trash_retention_days
+    }
+
+    pub fn get_terminal(&self) -> Option<&String> {
+        self.terminal.as_ref()
+    }
+
+    pub fn get_catalog(&self) -> Option<&PathBuf> {
+        self.catalog.as_ref()
+    }
+}
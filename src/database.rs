@@ -1,9 +1,24 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
 use sqlite::Value;
-use std::iter;
 
-use crate::utils::{generate_name, get_folder_path, Logs};
+use crate::error::{Error, Result};
+use crate::logs::Logs;
+use crate::objects::{Configuration, HistoryEntry, Pane, TrashEntry, UNSET, Workspace};
+use crate::utils::{encode_value, expand_home, generate_name, get_data_path, is_valid_host};
 pub const DB_NAME: &str = "my_shortcuts.db";
 
+/// The profile whose database is the one of the data folder.
+pub const DEFAULT_PROFILE: &str = "default";
+/// The folder of the databases of the other profiles, in the data folder.
+const PROFILES_FOLDER: &str = "profiles";
+const PROFILE_EXTENSION: &str = "db";
+
+/// The database chosen by `--db`, `$MYSHORTCUTS_DB` or the active profile.
+static DATABASE_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
 /// Used For the following databases : Oracle, PostgreSQL, Neo4j,
 pub const CLASSIC_SHEME: [&str; 6] = [
     "Host",
@@ -45,6 +60,61 @@ pub const REDIS_SCHEME: [&str; 6] = [
 ];
 /// Used for the shell command
 pub const CUSTOM_SHEME: [&str; 1] = ["Shell Command"];
+/// Rules of the CLASSIC_SHEME for PostgreSQL and Neo4j
+pub const CLASSIC_RULES: [Rule; 6] = [
+    Rule::optional(Check::Hostname),
+    Rule::optional(Check::Port),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the CLASSIC_SHEME for Oracle, which needs the credentials and the address
+pub const ORACLE_RULES: [Rule; 6] = [
+    Rule::required(Check::Hostname),
+    Rule::required(Check::Port),
+    Rule::required(Check::Any),
+    Rule::required(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the SOCKET_SCHEME
+pub const SOCKET_RULES: [Rule; 7] = [
+    Rule::optional(Check::Hostname),
+    Rule::optional(Check::Port),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::ExistingPath),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the FILE_SCHEME, sqlite3 creates the database if it doesn't exist
+pub const FILE_RULES: [Rule; 2] = [
+    Rule::required(Check::CreatablePath),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the MONGODB_SCHEME
+pub const MONGODB_RULES: [Rule; 6] = [
+    Rule::optional(Check::Hostname),
+    Rule::optional(Check::Port),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the REDIS_SCHEME
+pub const REDIS_RULES: [Rule; 6] = [
+    Rule::optional(Check::Hostname),
+    Rule::optional(Check::Port),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Any),
+    Rule::optional(Check::Number),
+    Rule::optional(Check::ExistingPath),
+];
+/// Rules of the CUSTOM_SHEME
+pub const CUSTOM_RULES: [Rule; 1] = [Rule::required(Check::Any)];
+/// Properties masked in the preview when the configurations are hidden.
+pub const SECRET_FIELDS: [&str; 1] = ["Password"];
 /// Used to choose what kind of shortcut you need.
 pub const AVAILABLE_SHEME: [&str; 9] = [
     "Oracle",
@@ -58,43 +128,201 @@ pub const AVAILABLE_SHEME: [&str; 9] = [
     "Custom",
 ];
 
+/// What a value must look like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+    Any,
+    /// A number between 1 and 65535
+    Port,
+    Number,
+    /// A hostname or an IP address
+    Hostname,
+    ExistingPath,
+    /// A path that exists or whose parent folder exists
+    CreatablePath,
+}
+
+/// Validation rule of a property of a scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    required: bool,
+    check: Check,
+}
+
+impl Rule {
+    pub const fn required(check: Check) -> Self {
+        Rule {
+            required: true,
+            check,
+        }
+    }
+
+    pub const fn optional(check: Check) -> Self {
+        Rule {
+            required: false,
+            check,
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub fn validate(&self, value: &str) -> Result<()> {
+        if value.is_empty() {
+            return if self.required {
+                Err(Error::Validation(String::from("This property is required")))
+            } else {
+                Ok(())
+            };
+        }
+        match self.check {
+            Check::Any => Ok(()),
+            Check::Port => match value.parse::<u16>() {
+                Ok(port) if port > 0 => Ok(()),
+                _ => Err(Error::Validation(String::from(
+                    "The port must be a number between 1 and 65535",
+                ))),
+            },
+            Check::Number => value
+                .parse::<u64>()
+                .map(|_| ())
+                .map_err(|_| Error::Validation(String::from("This property must be a number"))),
+            Check::Hostname => {
+                if is_valid_host(value) {
+                    Ok(())
+                } else {
+                    Err(Error::Validation(format!(
+                        "'{}' isn't a valid hostname or IP address",
+                        value
+                    )))
+                }
+            }
+            Check::ExistingPath => {
+                if expand_home(value).exists() {
+                    Ok(())
+                } else {
+                    Err(Error::Validation(format!("'{}' doesn't exist", value)))
+                }
+            }
+            Check::CreatablePath => {
+                let path = expand_home(value);
+                let parent_exists = path
+                    .parent()
+                    .map(|p| p.as_os_str().is_empty() || p.is_dir())
+                    .unwrap_or(false);
+                if path.exists() || parent_exists {
+                    Ok(())
+                } else {
+                    Err(Error::Validation(format!(
+                        "The folder of '{}' doesn't exist",
+                        value
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Get the validation rules of the properties of a kind of shortcut.
+pub fn get_rules(kind: &str) -> Vec<Rule> {
+    match kind {
+        "MySQL" | "MariaDB" => SOCKET_RULES.to_vec(),
+        "Oracle" => ORACLE_RULES.to_vec(),
+        "PostgreSQL" | "Neo4j" => CLASSIC_RULES.to_vec(),
+        "SQLite" => FILE_RULES.to_vec(),
+        "Redis" => REDIS_RULES.to_vec(),
+        "MongoDB" => MONGODB_RULES.to_vec(),
+        "Custom" => CUSTOM_RULES.to_vec(),
+        _ => vec![],
+    }
+}
+
+/// Get the names of the properties of the kind, in the order they are stored.
+pub fn get_scheme(kind: &str) -> Vec<&'static str> {
+    match kind {
+        "MySQL" | "MariaDB" => SOCKET_SCHEME.to_vec(),
+        "Oracle" | "PostgreSQL" | "Neo4j" => CLASSIC_SHEME.to_vec(),
+        "SQLite" => FILE_SCHEME.to_vec(),
+        "Redis" => REDIS_SCHEME.to_vec(),
+        "MongoDB" => MONGODB_SCHEME.to_vec(),
+        "Custom" => CUSTOM_SHEME.to_vec(),
+        _ => vec![],
+    }
+}
+
+/// Schema changes applied in order by `Database::migrate`.
+const MIGRATIONS: [&str; 4] = [
+    "CREATE TABLE IF NOT EXISTS trash (id INTEGER primary key, name TEXT, configuration TEXT, type TEXT, deleted_at INTEGER);",
+    // Escape the backslashes and replace the old 'Required' placeholder by the UNSET marker
+    r"UPDATE shortcuts SET configuration = substr(replace(replace(';' || replace(configuration, '\', '\\') || ';', ';Required;', ';\u;'), ';Required;', ';\u;'), 2);
+    UPDATE trash SET configuration = substr(replace(replace(';' || replace(configuration, '\', '\\') || ';', ';Required;', ';\u;'), ';Required;', ';\u;'), 2);",
+    "CREATE TABLE IF NOT EXISTS workspaces (name TEXT primary key, layout TEXT);
+    CREATE TABLE IF NOT EXISTS workspace_panes (workspace TEXT, position INTEGER, shortcut TEXT, window TEXT);",
+    "CREATE TABLE IF NOT EXISTS history (id INTEGER primary key, opened_at INTEGER, exit_code INTEGER, user TEXT, name TEXT, type TEXT, mode TEXT, command TEXT);
+    CREATE INDEX IF NOT EXISTS history_opened_at ON history (opened_at);",
+];
+
 pub struct Database;
 
 impl Database {
-    pub fn init() -> Result<(), String> {
+    pub fn init(path: &Path) -> Result<()> {
         let query = "
         DROP TABLE IF EXISTS shortcuts;
         CREATE TABLE shortcuts (name TEXT primary key, configuration TEXT, type TEXT);";
-        Database::query_write(query)
+        Database::query_write_at(path, query)
     }
 
-    pub fn query_write(query: &str) -> Result<(), String> {
-        let mut path = get_folder_path().map_err(|e| format!("{:?}", e))?;
-        path.push(DB_NAME);
+    /// Bring an existing database up to date, based on its `user_version`.
+    pub fn migrate(path: &Path) -> Result<()> {
+        let version = Database::query_read_at(path, "PRAGMA user_version;")?
+            .trim_end()
+            .trim_end_matches(';')
+            .parse::<usize>()
+            .unwrap_or(0);
 
-        let shortcut = sqlite::open(path).map_err(|e| format!("{e}"))?;
-        shortcut.execute(query).map_err(|e| format!("{e}"))
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            Database::query_write_at(
+                path,
+                &format!(
+                    "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+                    migration,
+                    index + 1
+                ),
+            )?;
+            Logs::info(
+                "database.rs - Database::migrate()",
+                format!("Migrated the database to the version {}", index + 1),
+            );
+        }
+        Ok(())
     }
 
-    pub fn query_read(query: &str) -> Result<String, String> {
-        let mut result = String::new();
-        let mut path = get_folder_path().map_err(|e| format!("{:?}", e))?;
-        path.push(DB_NAME);
+    pub fn query_write(query: &str) -> Result<()> {
+        Database::query_write_at(&get_database_path()?, query)
+    }
 
-        let shortcut = sqlite::open(path).map_err(|e| format!("{e}"))?;
+    /// Run the query on the database of another profile.
+    pub fn query_write_at(path: &Path, query: &str) -> Result<()> {
+        Logs::debug("database.rs - Database::query_write()", query);
+        let shortcut = sqlite::open(path)?;
+        Ok(shortcut.execute(query)?)
+    }
 
-        let mut cursor = shortcut
-            .prepare(query)
-            .map_err(|e| format!("{e}"))?
-            .into_iter();
+    pub fn query_read(query: &str) -> Result<String> {
+        Database::query_read_at(&get_database_path()?, query)
+    }
 
-        while let Some(tuple) = cursor.try_next().map_err(|e| format!("{e}"))? {
+    pub fn query_read_at(path: &Path, query: &str) -> Result<String> {
+        let mut result = String::new();
+        let shortcut = sqlite::open(path)?;
+
+        let mut cursor = shortcut.prepare(query)?.into_iter();
+
+        while let Some(tuple) = cursor.try_next()? {
             let mut line = String::new();
             for value in tuple {
-                line.push_str(&format!(
-                    "{};",
-                    extract_value(value).map_err(|e| e.to_string())?
-                ));
+                line.push_str(&format!("{};", extract_value(value)?));
             }
             result.push_str(&format!("{}\n", line));
         }
@@ -102,9 +330,330 @@ impl Database {
     }
 }
 
-fn extract_value(value: Value) -> Result<String, String> {
+/// Use this SQLite file instead of the one of the data folder.
+pub fn set_database_path(path: PathBuf) {
+    *DATABASE_PATH.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+}
+
+/// Get the path of the SQLite file holding the shortcuts.
+pub fn get_database_path() -> Result<PathBuf> {
+    if let Some(path) = DATABASE_PATH
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+    {
+        return Ok(path.clone());
+    }
+    let mut path = get_data_path()?;
+    path.push(DB_NAME);
+    Ok(path)
+}
+
+/// Get the SQLite file of the profile, the default one uses the database of the data folder.
+pub fn get_profile_path(name: &str) -> Result<PathBuf> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !is_valid {
+        return Err(Error::Validation(format!(
+            "The profile '{}' must only contain letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    let mut path = get_data_path()?;
+    if name == DEFAULT_PROFILE {
+        path.push(DB_NAME);
+    } else {
+        path.push(PROFILES_FOLDER);
+        path.push(format!("{}.{}", name, PROFILE_EXTENSION));
+    }
+    Ok(path)
+}
+
+/// Get the names of the profiles, the default one first.
+pub fn get_profiles() -> Result<Vec<String>> {
+    let mut folder = get_data_path()?;
+    folder.push(PROFILES_FOLDER);
+    let mut profiles = match fs::read_dir(&folder) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == PROFILE_EXTENSION))
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .filter(|name| name != DEFAULT_PROFILE && get_profile_path(name).is_ok())
+            .collect::<Vec<String>>(),
+        Err(_) => vec![],
+    };
+    profiles.sort();
+    profiles.insert(0, String::from(DEFAULT_PROFILE));
+    Ok(profiles)
+}
+
+/// Copy the shortcuts in the database of the profile, or move them with `remove`.<br>
+/// A shortcut whose name is taken there is renamed like `name (1)`, the new names are returned.
+pub fn copy_to_profile(names: &[String], profile: &str, remove: bool) -> Result<Vec<String>> {
+    let target = get_profile_path(profile)?;
+    if target == get_database_path()? {
+        return Err(Error::Validation(format!(
+            "The shortcuts are already in the profile '{}'",
+            profile
+        )));
+    }
+    Database::migrate(&target)?;
+
+    let mut taken = Database::query_read_at(&target, "SELECT name FROM shortcuts;")?
+        .lines()
+        .map(|line| String::from(line.strip_suffix(';').unwrap_or(line)))
+        .collect::<Vec<String>>();
+    let mut query = format!(
+        "ATTACH DATABASE '{}' AS target; BEGIN;",
+        escape(&target.to_string_lossy())
+    );
+    let mut copied = Vec::new();
+    for name in names {
+        let mut new_name = String::clone(name);
+        let mut index = 1;
+        while taken.contains(&new_name) {
+            new_name = format!("{} ({})", name, index);
+            index += 1;
+        }
+        query.push_str(&format!(
+            "INSERT INTO target.shortcuts (name, configuration, type)
+                SELECT '{}', configuration, type FROM main.shortcuts WHERE name='{}';",
+            escape(&new_name),
+            escape(name)
+        ));
+        if remove {
+            query.push_str(&format!(
+                "DELETE FROM main.shortcuts WHERE name='{}';",
+                escape(name)
+            ));
+        }
+        taken.push(String::clone(&new_name));
+        copied.push(new_name);
+    }
+    query.push_str("COMMIT; DETACH DATABASE target;");
+    Database::query_write(&query)?;
+    Ok(copied)
+}
+
+/// Move the shortcut in the trash instead of dropping it.
+pub fn move_to_trash(name: &str) -> Result<()> {
+    let name = escape(name);
+    Database::query_write(&format!(
+        "BEGIN;
+        INSERT INTO trash (name, configuration, type, deleted_at)
+            SELECT name, configuration, type, strftime('%s','now') FROM shortcuts WHERE name='{}';
+        DELETE FROM shortcuts WHERE name='{}';
+        COMMIT;",
+        name, name
+    ))
+}
+
+/// Put back the entry in the shortcuts, renamed when its name is already taken, and get
+/// the name it was restored under.
+pub fn restore_from_trash(entry: &TrashEntry, current_names: &[String]) -> Result<String> {
+    let mut name = String::clone(entry.get_name());
+    let mut index = 1;
+    while current_names.contains(&name) {
+        name = format!("{} ({})", entry.get_name(), index);
+        index += 1;
+    }
+    Database::query_write(&format!(
+        "BEGIN;
+        INSERT INTO shortcuts SELECT '{}', configuration, type FROM trash WHERE id={};
+        DELETE FROM trash WHERE id={};
+        COMMIT;",
+        escape(&name),
+        entry.get_id(),
+        entry.get_id()
+    ))?;
+    Ok(name)
+}
+
+pub fn purge_trash(id: Option<i64>) -> Result<()> {
+    match id {
+        Some(id) => Database::query_write(&format!("DELETE FROM trash WHERE id={};", id)),
+        None => Database::query_write("DELETE FROM trash;"),
+    }
+}
+
+/// Permanently delete the entries that stayed in the trash more than `days` days.
+pub fn purge_expired_trash(days: u64) -> Result<()> {
+    Database::query_write(&format!(
+        "DELETE FROM trash WHERE deleted_at < strftime('%s','now') - {};",
+        days.saturating_mul(86400)
+    ))
+}
+
+pub fn get_trash() -> Result<Vec<TrashEntry>> {
+    Ok(
+        Database::query_read(
+            "select id,deleted_at,type,name from trash order by deleted_at desc;",
+        )?
+        .split("\n")
+        .filter(|e| !e.is_empty())
+        .filter_map(|e| TrashEntry::parse(e).ok())
+        .collect(),
+    )
+}
+
+pub fn get_workspaces() -> Result<Vec<Workspace>> {
+    let mut workspaces = Vec::new();
+    for line in Database::query_read("select name,layout from workspaces order by name;")?
+        .split("\n")
+        .filter(|e| !e.is_empty())
+    {
+        let vector = line.split(";").collect::<Vec<&str>>();
+        let (Some(name), Some(layout)) = (vector.first(), vector.get(1)) else {
+            continue;
+        };
+        let panes = Database::query_read(&format!(
+            "select shortcut,window from workspace_panes where workspace='{}' order by position;",
+            escape(name)
+        ))?
+        .split("\n")
+        .filter(|e| !e.is_empty())
+        .filter_map(|e| Pane::parse(e).ok())
+        .collect();
+        workspaces.push(Workspace::from(name, layout, panes));
+    }
+    Ok(workspaces)
+}
+
+/// Save the workspace, in place of the workspace named `previous` when there is one.
+pub fn save_workspace(workspace: &Workspace, previous: Option<&str>) -> Result<()> {
+    let mut query = String::from("BEGIN;");
+    if let Some(previous) = previous {
+        query.push_str(&delete_workspace_query(previous));
+    }
+    query.push_str(&format!(
+        "INSERT INTO workspaces VALUES ('{}','{}');",
+        escape(workspace.get_name()),
+        escape(workspace.get_layout())
+    ));
+    for (position, pane) in workspace.get_panes().iter().enumerate() {
+        query.push_str(&format!(
+            "INSERT INTO workspace_panes VALUES ('{}',{},'{}','{}');",
+            escape(workspace.get_name()),
+            position,
+            escape(pane.get_shortcut()),
+            escape(pane.get_window())
+        ));
+    }
+    query.push_str("COMMIT;");
+    Database::query_write(&query)
+}
+
+pub fn delete_workspace(name: &str) -> Result<()> {
+    Database::query_write(&format!("BEGIN;{}COMMIT;", delete_workspace_query(name)))
+}
+
+fn delete_workspace_query(name: &str) -> String {
+    format!(
+        "DELETE FROM workspace_panes WHERE workspace='{}';DELETE FROM workspaces WHERE name='{}';",
+        escape(name),
+        escape(name)
+    )
+}
+
+pub fn add_history(entry: &HistoryEntry) -> Result<()> {
+    Database::query_write(&format!(
+        "INSERT INTO history (opened_at, exit_code, user, name, type, mode, command)
+            VALUES ({}, {}, '{}', '{}', '{}', '{}', '{}');",
+        entry.get_opened_at(),
+        entry
+            .get_exit_code()
+            .map_or(String::from("NULL"), |code| code.to_string()),
+        escape(&encode_value(entry.get_user())),
+        escape(&encode_value(entry.get_name())),
+        escape(&encode_value(entry.get_kind())),
+        escape(&encode_value(entry.get_mode())),
+        escape(&encode_value(entry.get_command()))
+    ))
+}
+
+/// Get the last `limit` entries of the history, the most recent first.<br>
+/// `name` only keeps a shortcut, `since` and `until` are Unix timestamps.
+pub fn get_history(
+    name: Option<&str>,
+    since: Option<i64>,
+    until: Option<i64>,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>> {
+    let mut conditions = vec![String::from("1")];
+    if let Some(name) = name {
+        conditions.push(format!("name='{}'", escape(&encode_value(name))));
+    }
+    if let Some(since) = since {
+        conditions.push(format!("opened_at >= {}", since));
+    }
+    if let Some(until) = until {
+        conditions.push(format!("opened_at < {}", until));
+    }
+    Ok(Database::query_read(&format!(
+        "select opened_at,exit_code,user,name,type,mode,command from history
+            where {} order by opened_at desc, id desc limit {};",
+        conditions.join(" and "),
+        limit
+    ))?
+    .split("\n")
+    .filter(|e| !e.is_empty())
+    .filter_map(|e| HistoryEntry::parse(e).ok())
+    .collect())
+}
+
+/// Get the values of the properties that must be masked.
+pub fn get_secrets(configurations: &[Configuration]) -> Vec<&String> {
+    configurations
+        .iter()
+        .filter(|c| SECRET_FIELDS.contains(&c.get_kind().as_str()))
+        .map(|c| c.get_value())
+        .collect()
+}
+
+/// Save the properties of the shortcut, formatted by `format_config!`.<br>
+/// The row is created the first time a shortcut of the catalog gets a local value.
+pub fn save_configurations(name: &str, kind: &str, configurations: &str) -> Result<()> {
+    Database::query_write(&format!(
+        "INSERT INTO shortcuts (name, configuration, type) VALUES ('{}', '{}', '{}')
+            ON CONFLICT(name) DO UPDATE SET configuration=excluded.configuration;",
+        escape(name),
+        escape(configurations),
+        escape(kind)
+    ))
+}
+
+/// Check if the shortcut has a row in the database, a shortcut of the catalog only gets one
+/// with its first local value.
+pub fn is_local(name: &str) -> Result<bool> {
+    Ok(!Database::query_read(&format!(
+        "SELECT name FROM shortcuts WHERE name='{}';",
+        escape(name)
+    ))?
+    .is_empty())
+}
+
+/// Delete the local values of a shortcut overriding the catalog, it gets back the values
+/// of the catalog.
+pub fn revert_to_catalog(name: &str) -> Result<()> {
+    Database::query_write(&format!(
+        "DELETE FROM shortcuts WHERE name='{}';",
+        escape(name)
+    ))
+}
+
+/// Escape the single quotes of a value interpolated in a query.
+pub fn escape(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+fn extract_value(value: Value) -> Result<String> {
     match value {
-        Value::Binary(vec) => String::from_utf8(vec).map_err(|e| format!("{e}")),
+        Value::Binary(vec) => {
+            String::from_utf8(vec).map_err(|e| Error::Parse(format!("Invalid text : {e}")))
+        }
         Value::Float(nb) => Ok(format!("{}", nb)),
         Value::Integer(nb) => Ok(format!("{}", nb)),
         Value::String(text) => Ok(text),
@@ -112,21 +661,63 @@ fn extract_value(value: Value) -> Result<String, String> {
     }
 }
 
-pub fn insert_default_config(current_names: Vec<String>, kind: &str, fields: usize) {
+/// Insert a new shortcut of this kind with its properties unset, get its generated name.
+pub fn insert_default_config(
+    current_names: Vec<String>,
+    kind: &str,
+    fields: usize,
+) -> Result<String> {
     let new_name = generate_name(current_names);
-    let config: String = iter::repeat("Required")
-        .take(fields)
-        .collect::<Vec<_>>()
-        .join(";");
-    let query = format!(
+    let config: String = format!("{};", UNSET).repeat(fields);
+    Database::query_write(&format!(
         "INSERT INTO shortcuts VALUES ('{}','{}','{}');",
-        new_name, config, kind
-    );
-    
-    if let Err(error) = Database::query_write(&query) {
-        Logs::write(format!(
-            "\nERROR : app.rs - add_new_shortcut() :\n{}\n|-> Name generated : '{}'",
-            error, new_name
-        ));
+        escape(&new_name),
+        config,
+        kind
+    ))?;
+    Ok(new_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::decode_config;
+    use std::{env, process};
+
+    #[test]
+    fn migrate_the_baseline_configurations() {
+        let path = env::temp_dir().join(format!("myshortcuts-test-{}-migrate.db", process::id()));
+        let _ = fs::remove_file(&path);
+        Database::init(&path).unwrap();
+        // The baseline joined the values with ';' and wrote 'Required' for the empty ones
+        Database::query_write_at(
+            &path,
+            r"INSERT INTO shortcuts VALUES ('pg', 'Required;5432;Required;pa\ss', 'PostgreSQL');
+            INSERT INTO shortcuts VALUES ('sh', 'echo C:\tmp', 'Custom');",
+        )
+        .unwrap();
+
+        Database::migrate(&path).unwrap();
+        let rows =
+            Database::query_read_at(&path, "SELECT configuration FROM shortcuts ORDER BY name;")
+                .unwrap();
+        let _ = fs::remove_file(&path);
+
+        let rows = rows
+            .lines()
+            .map(|row| decode_config(row.strip_suffix(';').unwrap_or(row)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    None,
+                    Some(String::from("5432")),
+                    None,
+                    Some(String::from(r"pa\ss"))
+                ],
+                vec![Some(String::from(r"echo C:\tmp"))],
+            ]
+        );
     }
 }
@@ -0,0 +1,583 @@
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+use toml::{Table, Value};
+use unicode_width::UnicodeWidthStr;
+
+use crate::database::Rule;
+use crate::error::{Error, Result};
+use crate::keymap::{Action, Context, Keymap};
+use crate::launcher::LAYOUTS;
+use crate::objects::{Configuration, Pane, Shortcut, Workspace};
+use crate::theme::Theme;
+use crate::ui::{centered_rect, key_hints};
+use crate::utils::is_valid_name;
+
+/// Words highlighted as bash keywords.
+const KEYWORDS: [&str; 20] = [
+    "if", "then", "elif", "else", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "in", "function", "return", "export", "local", "select", "break", "continue",
+];
+
+/// Characters that end a word in a shell command.
+const OPERATORS: [char; 7] = ['|', '&', ';', '<', '>', '(', ')'];
+
+/// Header of the file used to edit a whole shortcut in `$EDITOR`.
+const SHORTCUT_HEADER: &str = "\
+# Edit the shortcut, then save and quit. Quit without saving to cancel.
+# The commented properties are unset, uncomment them to give them a value.
+";
+
+const WORKSPACE_HEADER: &str = "\
+# Edit the workspace, then save and quit. Quit without saving to cancel.
+# The panes of a window are arranged with the layout :
+# even-horizontal, even-vertical, main-horizontal, main-vertical or tiled.
+";
+
+/// Multi-line editor of the Custom shell commands, shown in a modal.
+#[derive(Debug)]
+pub struct Editor {
+    /// The name of the edited property
+    title: String,
+    lines: Vec<String>,
+    /// The line and the column (in chars) of the cursor
+    cursor: (usize, usize),
+    /// The first line and the first column shown
+    scroll: (usize, usize),
+    /// The validation error of the value
+    error: Option<String>,
+}
+
+impl Editor {
+    pub fn from(title: &str, value: &str) -> Self {
+        let mut editor = Editor {
+            title: String::from(title),
+            lines: vec![],
+            cursor: (0, 0),
+            scroll: (0, 0),
+            error: None,
+        };
+        editor.set_value(value);
+        editor
+    }
+
+    pub fn get_value(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Replace the whole value, the cursor goes at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.lines = value.split('\n').map(String::from).collect();
+        let last = self.lines.len() - 1;
+        self.cursor = (last, self.lines[last].chars().count());
+    }
+
+    pub fn set_error(&mut self, error: Option<String>) {
+        self.error = error
+    }
+
+    /// Edit the value with a key that isn't bound to an action of the editor.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let (row, col) = self.cursor;
+        let len = self.lines[row].chars().count();
+        match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                let index = byte_index(&self.lines[row], col);
+                self.lines[row].insert(index, c);
+                self.cursor.1 += 1;
+            }
+            KeyCode::Tab => {
+                let index = byte_index(&self.lines[row], col);
+                self.lines[row].insert_str(index, "    ");
+                self.cursor.1 += 4;
+            }
+            KeyCode::Enter => {
+                let index = byte_index(&self.lines[row], col);
+                let tail = self.lines[row].split_off(index);
+                self.lines.insert(row + 1, tail);
+                self.cursor = (row + 1, 0);
+            }
+            KeyCode::Backspace if col > 0 => {
+                let index = byte_index(&self.lines[row], col - 1);
+                self.lines[row].remove(index);
+                self.cursor.1 -= 1;
+            }
+            KeyCode::Backspace if row > 0 => {
+                let line = self.lines.remove(row);
+                let previous = self.lines[row - 1].chars().count();
+                self.lines[row - 1].push_str(&line);
+                self.cursor = (row - 1, previous);
+            }
+            KeyCode::Delete if col < len => {
+                let index = byte_index(&self.lines[row], col);
+                self.lines[row].remove(index);
+            }
+            KeyCode::Delete if row + 1 < self.lines.len() => {
+                let line = self.lines.remove(row + 1);
+                self.lines[row].push_str(&line);
+            }
+            KeyCode::Left if col > 0 => self.cursor.1 -= 1,
+            KeyCode::Left if row > 0 => {
+                self.cursor = (row - 1, self.lines[row - 1].chars().count());
+            }
+            KeyCode::Right if col < len => self.cursor.1 += 1,
+            KeyCode::Right if row + 1 < self.lines.len() => self.cursor = (row + 1, 0),
+            KeyCode::Up if row > 0 => self.move_to_line(row - 1),
+            KeyCode::Down if row + 1 < self.lines.len() => self.move_to_line(row + 1),
+            KeyCode::Home => self.cursor.1 = 0,
+            KeyCode::End => self.cursor.1 = len,
+            _ => {}
+        }
+    }
+
+    fn move_to_line(&mut self, row: usize) {
+        let len = self.lines[row].chars().count();
+        self.cursor = (row, self.cursor.1.min(len));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, keymap: &Keymap, theme: &Theme) {
+        let screen = frame.area();
+        let area = centered_rect(
+            screen,
+            (screen.width * 4 / 5).max(40),
+            (screen.height * 4 / 5).max(8),
+        );
+
+        let mut block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .title(Line::from(format!(" {} ", self.title)).centered())
+            .title_bottom(
+                key_hints(
+                    keymap,
+                    Context::Editor,
+                    &[Action::Save, Action::External, Action::Cancel],
+                    theme,
+                )
+                .centered(),
+            )
+            .title_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme.header),
+            )
+            .bg(theme.background)
+            .fg(theme.font);
+        if let Some(error) = &self.error {
+            block = block
+                .border_style(Style::default().fg(theme.problem))
+                .title_top(Line::from(format!(" ⚠ {} ", error)).fg(theme.problem));
+        }
+        let inner = block.inner(area);
+
+        // The line numbers are in a gutter on the left
+        let gutter = self.lines.len().to_string().len() + 1;
+        let width = usize::from(inner.width).saturating_sub(gutter + 1).max(1);
+        let height = usize::from(inner.height).max(1);
+        self.scroll_to_cursor(width, height);
+
+        let lines = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll.0)
+            .take(height)
+            .map(|(index, line)| {
+                let mut spans =
+                    vec![Span::from(format!("{:>w$} ", index + 1, w = gutter - 1)).fg(theme.hint)];
+                spans.extend(skip_chars(highlight(line, theme), self.scroll.1));
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+
+        let (row, col) = self.cursor;
+        let before = self.lines[row]
+            .chars()
+            .skip(self.scroll.1)
+            .take(col - self.scroll.1)
+            .collect::<String>();
+        #[allow(clippy::cast_possible_truncation)]
+        frame.set_cursor_position((
+            inner.x + (gutter + before.width()) as u16,
+            inner.y + (row - self.scroll.0) as u16,
+        ));
+    }
+
+    /// Scroll the text so the cursor stays visible.
+    fn scroll_to_cursor(&mut self, width: usize, height: usize) {
+        let (row, col) = self.cursor;
+        if row < self.scroll.0 {
+            self.scroll.0 = row;
+        } else if row >= self.scroll.0 + height {
+            self.scroll.0 = row + 1 - height;
+        }
+        if col < self.scroll.1 {
+            self.scroll.1 = col;
+        } else if col >= self.scroll.1 + width {
+            self.scroll.1 = col + 1 - width;
+        }
+    }
+}
+
+/// Get the index of the byte where the char at `col` starts.
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices()
+        .nth(col)
+        .map(|(index, _)| index)
+        .unwrap_or(line.len())
+}
+
+/// Remove the first chars of the spans, for the horizontal scroll.
+fn skip_chars(spans: Vec<Span<'_>>, mut count: usize) -> Vec<Span<'_>> {
+    let mut result = Vec::new();
+    for span in spans {
+        let len = span.content.chars().count();
+        if count >= len {
+            count -= len;
+            continue;
+        }
+        let content = span.content.chars().skip(count).collect::<String>();
+        count = 0;
+        result.push(Span::styled(content, span.style));
+    }
+    result
+}
+
+/// Split a line of bash in spans colored by kind: keywords, strings, variables, operators and comments.
+fn highlight<'a>(line: &str, theme: &Theme) -> Vec<Span<'a>> {
+    let chars = line.chars().collect::<Vec<char>>();
+    let mut spans: Vec<Span> = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let start = index;
+        let c = chars[index];
+        let span = if c == '#' && (index == 0 || chars[index - 1].is_whitespace()) {
+            index = chars.len();
+            Span::from(chars[start..].iter().collect::<String>())
+                .fg(theme.hint)
+                .italic()
+        } else if c == '\'' || c == '"' {
+            index += 1;
+            while index < chars.len() && chars[index] != c {
+                // Only the double quotes can be escaped
+                if c == '"' && chars[index] == '\\' {
+                    index += 1;
+                }
+                index += 1;
+            }
+            index = (index + 1).min(chars.len());
+            Span::from(chars[start..index].iter().collect::<String>()).fg(theme.cell_editing)
+        } else if c == '$' {
+            index += 1;
+            if chars.get(index) == Some(&'{') {
+                while index < chars.len() && chars[index] != '}' {
+                    index += 1;
+                }
+                index = (index + 1).min(chars.len());
+            } else if chars
+                .get(index)
+                .is_some_and(|c| c.is_ascii_digit() || "?#@*!$-".contains(*c))
+            {
+                index += 1;
+            } else {
+                while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_')
+                {
+                    index += 1;
+                }
+            }
+            Span::from(chars[start..index].iter().collect::<String>()).fg(theme.column_selected)
+        } else if OPERATORS.contains(&c) {
+            while index < chars.len() && OPERATORS.contains(&chars[index]) {
+                index += 1;
+            }
+            Span::from(chars[start..index].iter().collect::<String>()).fg(theme.key)
+        } else if c.is_whitespace() {
+            while index < chars.len() && chars[index].is_whitespace() {
+                index += 1;
+            }
+            Span::from(chars[start..index].iter().collect::<String>())
+        } else {
+            while index < chars.len()
+                && !chars[index].is_whitespace()
+                && !OPERATORS.contains(&chars[index])
+                && !"'\"$".contains(chars[index])
+            {
+                index += 1;
+            }
+            let word = chars[start..index].iter().collect::<String>();
+            if KEYWORDS.contains(&word.as_str()) {
+                Span::from(word).fg(theme.header).bold()
+            } else {
+                Span::from(word)
+            }
+        };
+        spans.push(span);
+    }
+    spans
+}
+
+/// Write a shortcut and its properties as TOML, to edit them in `$EDITOR`.
+pub fn shortcut_to_toml(shortcut: &Shortcut, configurations: &[Configuration]) -> String {
+    let mut content = String::from(SHORTCUT_HEADER);
+    content.push_str(&format!(
+        "\nname = {}\nkind = {}\n\n[properties]\n",
+        quote(shortcut.get_name()),
+        quote(shortcut.get_kind())
+    ));
+    for configuration in configurations {
+        if configuration.is_inherited() {
+            // Left unset, it keeps following the catalog
+            content.push_str(&format!(
+                "# {} = {} # from the catalog\n",
+                toml_key(configuration.get_kind()),
+                quote(configuration.get_value())
+            ));
+        } else if configuration.is_unset() {
+            content.push_str(&format!(
+                "# {} = \"\"\n",
+                toml_key(configuration.get_kind())
+            ));
+        } else {
+            content.push_str(&format!(
+                "{} = {}\n",
+                toml_key(configuration.get_kind()),
+                quote(configuration.get_value())
+            ));
+        }
+    }
+    content
+}
+
+/// Read the shortcut edited in `$EDITOR`, its properties are checked against the rules of its scheme.<br>
+/// `names` are the names of the other shortcuts.
+pub fn shortcut_from_toml(
+    content: &str,
+    shortcut: &Shortcut,
+    configurations: &[Configuration],
+    rules: &[Rule],
+    names: &[String],
+) -> Result<(String, Vec<Configuration>)> {
+    let table = content.parse::<Table>()?;
+    if let Some(key) = table
+        .keys()
+        .find(|k| !["name", "kind", "properties"].contains(&k.as_str()))
+    {
+        return Err(Error::Parse(format!("Unknown key '{}'", key)));
+    }
+
+    let name = table
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(Error::Validation(String::from("The name is required")))?;
+    if !is_valid_name(name) {
+        return Err(Error::Validation(String::from(
+            "The name can't contain ';' or control characters",
+        )));
+    }
+    if name != shortcut.get_name() && names.iter().any(|n| n == name) {
+        return Err(Error::Validation(format!(
+            "A shortcut named '{}' already exists",
+            name
+        )));
+    }
+    if table.get("kind").and_then(|k| k.as_str()) != Some(shortcut.get_kind().as_str()) {
+        return Err(Error::Validation(format!(
+            "The kind must stay '{}'",
+            shortcut.get_kind()
+        )));
+    }
+
+    let properties = match table.get("properties") {
+        Some(Value::Table(properties)) => properties.clone(),
+        Some(_) => return Err(Error::Parse(String::from("'properties' must be a table"))),
+        None => Table::new(),
+    };
+    if let Some(key) = properties
+        .keys()
+        .find(|k| !configurations.iter().any(|c| c.get_kind() == *k))
+    {
+        return Err(Error::Parse(format!(
+            "Unknown property '{}' for {}",
+            key,
+            shortcut.get_kind()
+        )));
+    }
+
+    let mut errors = Vec::new();
+    let mut result = Vec::new();
+    for (index, configuration) in configurations.iter().enumerate() {
+        let kind = configuration.get_kind();
+        let value = match properties.get(kind) {
+            None => {
+                result.push(Configuration::unset(kind));
+                continue;
+            }
+            Some(Value::String(value)) => String::clone(value),
+            Some(Value::Integer(value)) => value.to_string(),
+            Some(_) => {
+                errors.push(format!("{} : The value must be a string", kind));
+                continue;
+            }
+        };
+        if let Some(Err(error)) = rules.get(index).map(|r| r.validate(&value)) {
+            errors.push(format!("{} : {}", kind, error));
+        }
+        result.push(Configuration::from(&value, kind));
+    }
+
+    if errors.is_empty() {
+        Ok((String::from(name), result))
+    } else {
+        Err(Error::Validation(errors.join("\n")))
+    }
+}
+
+pub fn workspace_to_toml(workspace: &Workspace) -> String {
+    let mut content = String::from(WORKSPACE_HEADER);
+    content.push_str(&format!(
+        "\nname = {}\nlayout = {}\n",
+        quote(workspace.get_name()),
+        quote(workspace.get_layout())
+    ));
+    for pane in workspace.get_panes() {
+        content.push_str(&format!(
+            "\n[[panes]]\nshortcut = {}\nwindow = {}\n",
+            quote(pane.get_shortcut()),
+            quote(pane.get_window())
+        ));
+    }
+    content
+}
+
+/// Read the workspace edited in `$EDITOR`.<br>
+/// `names` are the names of the other workspaces and `shortcuts` the names of the shortcuts.
+pub fn workspace_from_toml(
+    content: &str,
+    workspace: &Workspace,
+    names: &[String],
+    shortcuts: &[String],
+) -> Result<Workspace> {
+    let table = content.parse::<Table>()?;
+    if let Some(key) = table
+        .keys()
+        .find(|k| !["name", "layout", "panes"].contains(&k.as_str()))
+    {
+        return Err(Error::Parse(format!("Unknown key '{}'", key)));
+    }
+
+    let name = table
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or(Error::Validation(String::from("The name is required")))?;
+    if !is_valid_name(name) {
+        return Err(Error::Validation(String::from(
+            "The name can't contain ';' or control characters",
+        )));
+    }
+    if name != workspace.get_name() && names.iter().any(|n| n == name) {
+        return Err(Error::Validation(format!(
+            "A workspace named '{}' already exists",
+            name
+        )));
+    }
+    let layout = table
+        .get("layout")
+        .and_then(|l| l.as_str())
+        .ok_or(Error::Validation(String::from("The layout is required")))?;
+    if !LAYOUTS.contains(&layout) {
+        return Err(Error::Validation(format!(
+            "Unknown layout '{}', expected {}",
+            layout,
+            LAYOUTS.join(", ")
+        )));
+    }
+
+    let Some(Value::Array(array)) = table.get("panes") else {
+        return Err(Error::Validation(String::from(
+            "At least one [[panes]] is required",
+        )));
+    };
+    let mut errors = Vec::new();
+    let mut panes = Vec::new();
+    for (index, pane) in array.iter().enumerate() {
+        let shortcut = pane.get("shortcut").and_then(|s| s.as_str());
+        let window = pane
+            .get("window")
+            .and_then(|w| w.as_str())
+            .map(str::trim)
+            .filter(|w| !w.is_empty());
+        match (shortcut, window) {
+            (Some(_), Some(window)) if !is_valid_name(window) => errors.push(format!(
+                "Pane {} : The window can't contain ';' or control characters",
+                index + 1
+            )),
+            (Some(shortcut), Some(window)) if shortcuts.iter().any(|s| s == shortcut) => {
+                panes.push(Pane::from(shortcut, window))
+            }
+            (Some(shortcut), Some(_)) => errors.push(format!(
+                "Pane {} : There is no shortcut named '{}'",
+                index + 1,
+                shortcut
+            )),
+            _ => errors.push(format!(
+                "Pane {} : 'shortcut' and 'window' are required",
+                index + 1
+            )),
+        }
+    }
+    if panes.is_empty() && errors.is_empty() {
+        errors.push(String::from("At least one [[panes]] is required"));
+    }
+
+    if errors.is_empty() {
+        Ok(Workspace::from(name, layout, panes))
+    } else {
+        Err(Error::Validation(errors.join("\n")))
+    }
+}
+
+/// Put the error at the top of the file as comments, in place of the previous one.
+pub fn with_error(content: &str, error: &str) -> String {
+    let mut result = error
+        .lines()
+        .map(|line| format!("# ERROR : {}\n", line))
+        .collect::<String>();
+    for line in content.lines().skip_while(|l| l.starts_with("# ERROR : ")) {
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+fn quote(value: &str) -> String {
+    Value::String(String::from(value)).to_string()
+}
+
+/// Quote the keys that aren't bare keys, like `"Shell Command"`.
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        String::from(key)
+    } else {
+        quote(key)
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong in the app.<br>
+/// The message of the variants is shown as is to the user, in the TUI or on the terminal.
+#[derive(Debug)]
+pub enum Error {
+    /// A file, or an external program like tmux or `$EDITOR`, failed
+    Io(io::Error),
+    Sqlite(sqlite::Error),
+    /// A stored value, a file or an argument isn't well formed
+    Parse(String),
+    /// A value is rejected by the rules of its property
+    Validation(String),
+    /// Required properties of a shortcut are unset
+    MissingField(Vec<String>),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Wrap an error of an external program, like `tmux new-window failed : ...`.
+    pub fn command(message: String) -> Self {
+        Error::Io(io::Error::other(message))
+    }
+
+    /// Add what was being done to the message of an IO error.
+    pub fn io(context: String, error: io::Error) -> Self {
+        Error::Io(io::Error::new(
+            error.kind(),
+            format!("{} : {}", context, error),
+        ))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Sqlite(error) => write!(f, "Database error : {}", error),
+            Error::Parse(message) | Error::Validation(message) => write!(f, "{}", message),
+            Error::MissingField(fields) => {
+                write!(f, "Missing required properties : {}", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// The message of the cause is already part of the message, it isn't given as the source.
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<sqlite::Error> for Error {
+    fn from(error: sqlite::Error) -> Self {
+        Error::Sqlite(error)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
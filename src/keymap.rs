@@ -0,0 +1,617 @@
+use std::fmt;
+use std::fs;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use toml::{Table, Value};
+
+use crate::error::{Error, Result};
+use crate::logs::Logs;
+use crate::utils::get_config_path;
+
+pub const KEYMAP_NAME: &str = "keymap.toml";
+
+/// Where the user is, the same key can trigger different actions in each context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Normal,
+    Editing,
+    PopUp,
+    Trash,
+    Confirmation,
+    Alert,
+    Editor,
+    Workspaces,
+    History,
+    Profiles,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Open,
+    Add,
+    Remove,
+    Edit,
+    Save,
+    Hide,
+    Theme,
+    Compact,
+    Help,
+    Trash,
+    Quit,
+    Select,
+    Restore,
+    Purge,
+    PurgeAll,
+    Confirm,
+    Cancel,
+    Close,
+    External,
+    Mark,
+    Workspaces,
+    History,
+    Profiles,
+    Copy,
+    Move,
+}
+
+/// A key with its modifiers, like `Ctrl-d`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+/// Bindings of every context, loaded from `keymap.toml` in the configuration folder.<br>
+/// A binding is a sequence of keys separated by spaces, like `g g` or `Ctrl-x Ctrl-s`.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Context, Action, Vec<Key>)>,
+    /// The keys already pressed of an unfinished sequence
+    pending: Vec<Key>,
+}
+
+/// The sequences given to an action of a context by the keymap file.
+type Overrides = (Context, Action, Vec<Vec<Key>>);
+
+const CONTEXTS: [(Context, &str); 10] = [
+    (Context::Normal, "normal"),
+    (Context::Editing, "editing"),
+    (Context::PopUp, "pop_up"),
+    (Context::Trash, "trash"),
+    (Context::Confirmation, "confirmation"),
+    (Context::Alert, "alert"),
+    (Context::Editor, "editor"),
+    (Context::Workspaces, "workspaces"),
+    (Context::History, "history"),
+    (Context::Profiles, "profiles"),
+];
+
+/// The name of each action in the keymap file and its description in the help.
+const ACTIONS: [(Action, &str, &str); 33] = [
+    (Action::Up, "up", "Move up"),
+    (Action::Down, "down", "Move down"),
+    (Action::Left, "left", "Move left"),
+    (Action::Right, "right", "Move right"),
+    (Action::PageUp, "page_up", "Move up a page"),
+    (Action::PageDown, "page_down", "Move down a page"),
+    (Action::First, "first", "Go to the first row"),
+    (Action::Last, "last", "Go to the last row"),
+    (Action::Open, "open", "Open the selected shortcut"),
+    (Action::Add, "add", "Add new shortcut"),
+    (Action::Remove, "remove", "Remove shortcut/config"),
+    (Action::Edit, "edit", "Edit shortcut/config"),
+    (Action::Save, "save", "Save changes"),
+    (Action::Hide, "hide", "Hidde/Show configs"),
+    (Action::Theme, "theme", "Switch theme"),
+    (Action::Compact, "compact", "Compact/Large rows"),
+    (Action::Help, "help", "Show/Hide help"),
+    (Action::Trash, "trash", "Trash bin"),
+    (Action::Quit, "quit", "Quit"),
+    (Action::Select, "select", "Select"),
+    (Action::Restore, "restore", "Restore"),
+    (Action::Purge, "purge", "Purge"),
+    (Action::PurgeAll, "purge_all", "Purge all"),
+    (Action::Confirm, "confirm", "Confirm"),
+    (Action::Cancel, "cancel", "Cancel"),
+    (Action::Close, "close", "Close"),
+    (Action::External, "external", "Open in $EDITOR"),
+    (Action::Mark, "mark", "Mark/Unmark shortcut"),
+    (Action::Workspaces, "workspaces", "Workspaces"),
+    (Action::History, "history", "History"),
+    (Action::Profiles, "profiles", "Profiles"),
+    (Action::Copy, "copy", "Copy to the profile"),
+    (Action::Move, "move", "Move to the profile"),
+];
+
+const DEFAULT_BINDINGS: [(Context, Action, &[&str]); 56] = [
+    (Context::Normal, Action::Up, &["Up"]),
+    (Context::Normal, Action::Down, &["Down"]),
+    (Context::Normal, Action::Left, &["Left"]),
+    (Context::Normal, Action::Right, &["Right"]),
+    (Context::Normal, Action::PageUp, &["PageUp"]),
+    (Context::Normal, Action::PageDown, &["PageDown"]),
+    (Context::Normal, Action::First, &["Home"]),
+    (Context::Normal, Action::Last, &["End"]),
+    (Context::Normal, Action::Add, &["a", "A"]),
+    (Context::Normal, Action::Remove, &["r", "R"]),
+    (Context::Normal, Action::Open, &["o", "O"]),
+    (Context::Normal, Action::Edit, &["e", "E"]),
+    (Context::Normal, Action::Hide, &["h", "H"]),
+    (Context::Normal, Action::External, &["Ctrl-e"]),
+    (Context::Normal, Action::Theme, &["c", "C"]),
+    (Context::Normal, Action::Compact, &["z", "Z"]),
+    (Context::Normal, Action::Help, &["?"]),
+    (Context::Normal, Action::Mark, &["Space"]),
+    (Context::Normal, Action::Workspaces, &["w", "W"]),
+    (Context::Normal, Action::Trash, &["t", "T"]),
+    (Context::Normal, Action::History, &["Ctrl-r"]),
+    (Context::Normal, Action::Profiles, &["p", "P"]),
+    (Context::Normal, Action::Quit, &["q", "Q", "Esc"]),
+    (Context::Editing, Action::Save, &["Enter"]),
+    (Context::PopUp, Action::Up, &["Up"]),
+    (Context::PopUp, Action::Down, &["Down"]),
+    (Context::PopUp, Action::Select, &["Enter"]),
+    (Context::PopUp, Action::Cancel, &["q", "Q", "Esc"]),
+    (Context::Trash, Action::Up, &["Up"]),
+    (Context::Trash, Action::Down, &["Down"]),
+    (Context::Trash, Action::Restore, &["u", "U"]),
+    (Context::Trash, Action::Purge, &["p"]),
+    (Context::Trash, Action::PurgeAll, &["P"]),
+    (Context::Trash, Action::Close, &["q", "Q", "t", "Esc"]),
+    (Context::Confirmation, Action::Confirm, &["y", "Y", "Enter"]),
+    (
+        Context::Confirmation,
+        Action::Cancel,
+        &["n", "N", "q", "Esc"],
+    ),
+    (Context::Alert, Action::Close, &["Enter", "q", "Esc"]),
+    (Context::Editor, Action::Save, &["Ctrl-s"]),
+    (Context::Editor, Action::External, &["Ctrl-e"]),
+    (Context::Editor, Action::Cancel, &["Esc"]),
+    (Context::Workspaces, Action::Up, &["Up"]),
+    (Context::Workspaces, Action::Down, &["Down"]),
+    (Context::Workspaces, Action::Open, &["o", "O", "Enter"]),
+    (Context::Workspaces, Action::Add, &["a", "A"]),
+    (Context::Workspaces, Action::Edit, &["e", "E", "Ctrl-e"]),
+    (Context::Workspaces, Action::Remove, &["r", "R"]),
+    (Context::Workspaces, Action::Close, &["q", "Q", "w", "Esc"]),
+    (Context::History, Action::Up, &["Up"]),
+    (Context::History, Action::Down, &["Down"]),
+    (
+        Context::History,
+        Action::Close,
+        &["q", "Q", "Ctrl-r", "Esc"],
+    ),
+    (Context::Profiles, Action::Up, &["Up"]),
+    (Context::Profiles, Action::Down, &["Down"]),
+    (Context::Profiles, Action::Select, &["Enter"]),
+    (Context::Profiles, Action::Copy, &["c", "C"]),
+    (Context::Profiles, Action::Move, &["m", "M"]),
+    (Context::Profiles, Action::Close, &["q", "Q", "p", "Esc"]),
+];
+
+impl Key {
+    pub fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers & !KeyModifiers::SHIFT;
+        // The case of the character already tells if Shift is pressed
+        if !matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= event.modifiers & KeyModifiers::SHIFT;
+        }
+        Key {
+            code: event.code,
+            modifiers,
+        }
+    }
+
+    /// Parse a key like `k`, `Enter`, `Ctrl-d`, `Alt-Shift-Up`
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = value;
+        while let Some((modifier, tail)) = rest.split_once('-').filter(|(_, t)| !t.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => {
+                    return Err(Error::Parse(format!(
+                        "Unknown modifier '{}' in '{}'",
+                        modifier, value
+                    )));
+                }
+            };
+            rest = tail;
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            key if key.len() > 1 && key.starts_with('f') => key[1..]
+                .parse::<u8>()
+                .map(KeyCode::F)
+                .map_err(|_| Error::Parse(format!("Unknown key '{}'", value)))?,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        modifiers.remove(KeyModifiers::SHIFT);
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(Error::Parse(format!("Unknown key '{}'", value))),
+                }
+            }
+        };
+        Ok(Key { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn default() -> Self {
+        let mut bindings = Vec::new();
+        for (context, action, keys) in DEFAULT_BINDINGS {
+            for sequence in keys {
+                if let Ok(sequence) = parse_sequence(sequence) {
+                    bindings.push((context, action, sequence));
+                }
+            }
+        }
+        Keymap {
+            bindings,
+            pending: vec![],
+        }
+    }
+
+    /// Load the keymap file, the actions it doesn't mention keep their default bindings.
+    pub fn load() -> Self {
+        let mut keymap = Keymap::default();
+        let Ok(mut path) = get_config_path() else {
+            return keymap;
+        };
+        path.push(KEYMAP_NAME);
+
+        let Ok(content) = fs::read_to_string(&path) else {
+            return keymap;
+        };
+        match content.parse::<Table>() {
+            Ok(table) => {
+                if let Err(error) = keymap.merge(table) {
+                    Logs::error("keymap.rs - Keymap::load()", error);
+                }
+            }
+            Err(error) => Logs::error("keymap.rs - Keymap::load()", error),
+        }
+        keymap
+    }
+
+    /// Replace the bindings of the actions listed in the table.<br>
+    /// The whole table is checked first, an error leaves the keymap untouched.
+    /// A sequence already bound to another action of the context is taken from it.
+    fn merge(&mut self, table: Table) -> Result<()> {
+        let overrides = parse_table(table)?;
+
+        for (context, action, _) in &overrides {
+            self.bindings
+                .retain(|(c, a, _)| !(c == context && a == action));
+        }
+        let mut merged: Vec<(Context, Action, Vec<Key>)> = Vec::new();
+        for (context, action, sequences) in overrides {
+            for sequence in sequences {
+                self.bindings
+                    .retain(|(c, _, s)| !(*c == context && *s == sequence));
+                if let Some((_, other, _)) = merged
+                    .iter()
+                    .find(|(c, a, s)| *c == context && *a != action && *s == sequence)
+                {
+                    Logs::warn(
+                        "keymap.rs - Keymap::merge()",
+                        format!(
+                            "'{}' is bound to '{}' and '{}' in {}, only '{}' is kept",
+                            format_sequence(&sequence),
+                            get_action_name(*other),
+                            get_action_name(action),
+                            context.get_name(),
+                            get_action_name(action)
+                        ),
+                    );
+                }
+                merged.retain(|(c, _, s)| !(*c == context && *s == sequence));
+                merged.push((context, action, sequence));
+            }
+        }
+        self.bindings.extend(merged);
+
+        // `resolve` stops at the shortest sequence, the longer one can't be typed anymore
+        for (context, action, sequence) in &self.bindings {
+            if let Some((_, other, prefix)) = self.bindings.iter().find(|(c, _, s)| {
+                c == context && s.len() < sequence.len() && sequence.starts_with(s)
+            }) {
+                Logs::warn(
+                    "keymap.rs - Keymap::merge()",
+                    format!(
+                        "'{}' of '{}' is unreachable in {}, '{}' is bound to '{}'",
+                        format_sequence(sequence),
+                        get_action_name(*action),
+                        context.get_name(),
+                        format_sequence(prefix),
+                        get_action_name(*other)
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the action triggered by the key in this context.<br>
+    /// Returns `None` while the key starts a longer sequence.
+    pub fn resolve(&mut self, context: Context, event: KeyEvent) -> Option<Action> {
+        self.pending.push(Key::from(event));
+
+        loop {
+            let candidates = self
+                .bindings
+                .iter()
+                .filter(|(c, _, sequence)| *c == context && sequence.starts_with(&self.pending))
+                .collect::<Vec<_>>();
+
+            if let Some((_, action, _)) = candidates
+                .iter()
+                .find(|(_, _, sequence)| *sequence == self.pending)
+            {
+                let action = *action;
+                self.pending.clear();
+                return Some(action);
+            }
+            if !candidates.is_empty() {
+                return None;
+            }
+            if self.pending.len() <= 1 {
+                self.pending.clear();
+                return None;
+            }
+            // The sequence is broken, the last key may start a new one
+            self.pending.drain(..self.pending.len() - 1);
+        }
+    }
+
+    /// Get the bindings of an action formatted for the help.
+    pub fn get_keys(&self, context: Context, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(c, a, _)| *c == context && *a == action)
+            .map(|(_, _, sequence)| format_sequence(sequence))
+            .collect()
+    }
+}
+
+impl Context {
+    /// Get the name of the context, as written in the keymap file.
+    pub fn get_name(&self) -> &'static str {
+        CONTEXTS
+            .iter()
+            .find(|(context, _)| context == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+}
+
+pub fn get_description(action: Action) -> &'static str {
+    ACTIONS
+        .iter()
+        .find(|(a, _, _)| *a == action)
+        .map(|(_, _, description)| *description)
+        .unwrap_or_default()
+}
+
+fn parse_sequence(sequence: &str) -> Result<Vec<Key>> {
+    sequence.split_whitespace().map(Key::parse).collect()
+}
+
+/// Parse the bindings of the keymap file, by context and action.
+fn parse_table(table: Table) -> Result<Vec<Overrides>> {
+    let mut overrides = Vec::new();
+    for (context_name, actions) in table {
+        let context = CONTEXTS
+            .iter()
+            .find(|(_, name)| *name == context_name)
+            .map(|(context, _)| *context)
+            .ok_or(Error::Parse(format!("Unknown context '{}'", context_name)))?;
+        let actions = actions
+            .as_table()
+            .ok_or(Error::Parse(format!("'{}' must be a table", context_name)))?;
+
+        for (action_name, keys) in actions {
+            let action = ACTIONS
+                .iter()
+                .find(|(_, name, _)| name == action_name)
+                .map(|(action, _, _)| *action)
+                .ok_or(Error::Parse(format!("Unknown action '{}'", action_name)))?;
+            let keys = match keys {
+                Value::String(key) => vec![key.as_str()],
+                Value::Array(keys) => keys.iter().filter_map(|k| k.as_str()).collect(),
+                _ => return Err(Error::Parse(format!("Invalid keys for '{}'", action_name))),
+            };
+            let sequences = keys
+                .into_iter()
+                .map(parse_sequence)
+                .collect::<Result<Vec<Vec<Key>>>>()?;
+            overrides.push((context, action, sequences));
+        }
+    }
+    Ok(overrides)
+}
+
+fn format_sequence(sequence: &[Key]) -> String {
+    sequence
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn get_action_name(action: Action) -> &'static str {
+    ACTIONS
+        .iter()
+        .find(|(a, _, _)| *a == action)
+        .map(|(_, name, _)| *name)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        Key { code, modifiers }
+    }
+
+    fn press(keymap: &mut Keymap, context: Context, c: char) -> Option<Action> {
+        keymap.resolve(context, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    fn merge(keymap: &mut Keymap, content: &str) -> Result<()> {
+        keymap.merge(content.parse::<Table>().unwrap())
+    }
+
+    #[test]
+    fn parse_key_with_modifiers() {
+        let ctrl_d = key(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(Key::parse("Ctrl-d").unwrap(), ctrl_d);
+        assert_eq!(Key::parse("c-d").unwrap(), ctrl_d);
+        assert_eq!(Key::parse("C-d").unwrap(), ctrl_d);
+        assert_eq!(
+            Key::parse("m-Enter").unwrap(),
+            key(KeyCode::Enter, KeyModifiers::ALT)
+        );
+        assert_eq!(
+            Key::parse("Alt-Shift-Up").unwrap(),
+            key(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            Key::parse("Shift-g").unwrap(),
+            key(KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("-").unwrap(),
+            key(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("Ctrl--").unwrap(),
+            key(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            Key::parse("F12").unwrap(),
+            key(KeyCode::F(12), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_keys() {
+        assert!(Key::parse("Hyper-d").is_err());
+        assert!(Key::parse("Ctrl-dd").is_err());
+        assert!(Key::parse("Fx").is_err());
+        assert!(Key::parse("").is_err());
+    }
+
+    #[test]
+    fn merge_nothing_on_error() {
+        let mut keymap = Keymap::default();
+        let bindings = keymap.bindings.clone();
+        assert!(merge(&mut keymap, "[normal]\nup = \"k\"\ndown = \"Hyper-j\"").is_err());
+        assert!(merge(&mut keymap, "[normal]\nup = \"k\"\n[nowhere]\nup = \"k\"").is_err());
+        assert!(merge(&mut keymap, "[normal]\nup = \"k\"\nfly = \"f\"").is_err());
+        assert_eq!(keymap.bindings, bindings);
+    }
+
+    #[test]
+    fn merge_replaces_the_defaults() {
+        let mut keymap = Keymap::default();
+        merge(&mut keymap, "[normal]\nup = [\"k\", \"Ctrl-p\"]").unwrap();
+        assert_eq!(
+            keymap.get_keys(Context::Normal, Action::Up),
+            ["k", "Ctrl-p"]
+        );
+        assert_eq!(keymap.get_keys(Context::Normal, Action::Down), ["Down"]);
+    }
+
+    #[test]
+    fn merge_steals_the_duplicate_sequences() {
+        let mut keymap = Keymap::default();
+        merge(&mut keymap, "[normal]\nup = \"k\"\ndown = [\"j\", \"Up\"]").unwrap();
+        assert_eq!(keymap.get_keys(Context::Normal, Action::Up), ["k"]);
+        assert_eq!(keymap.get_keys(Context::Normal, Action::Down), ["j", "Up"]);
+        // Only the context of the table is changed
+        assert_eq!(keymap.get_keys(Context::Trash, Action::Up), ["Up"]);
+    }
+
+    #[test]
+    fn resolve_chords() {
+        let mut keymap = Keymap::default();
+        merge(&mut keymap, "[normal]\nfirst = \"g g\"\nlast = \"G\"").unwrap();
+        assert_eq!(press(&mut keymap, Context::Normal, 'g'), None);
+        assert_eq!(
+            press(&mut keymap, Context::Normal, 'g'),
+            Some(Action::First)
+        );
+        assert_eq!(press(&mut keymap, Context::Normal, 'G'), Some(Action::Last));
+    }
+
+    #[test]
+    fn resolve_broken_chords() {
+        let mut keymap = Keymap::default();
+        merge(&mut keymap, "[normal]\nfirst = \"g g\"\nlast = \"G\"").unwrap();
+        // The last key of a broken sequence is resolved on its own
+        assert_eq!(press(&mut keymap, Context::Normal, 'g'), None);
+        assert_eq!(press(&mut keymap, Context::Normal, 'G'), Some(Action::Last));
+        assert_eq!(press(&mut keymap, Context::Normal, 'g'), None);
+        assert_eq!(press(&mut keymap, Context::Normal, 'x'), None);
+        assert_eq!(press(&mut keymap, Context::Normal, 'g'), None);
+        assert_eq!(
+            press(&mut keymap, Context::Normal, 'g'),
+            Some(Action::First)
+        );
+    }
+}
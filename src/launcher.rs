@@ -0,0 +1,363 @@
+use std::env;
+use std::process::{Command, Stdio};
+
+use crate::config::Settings;
+use crate::error::{Error, Result};
+use crate::objects::Workspace;
+
+/// Placeholder of the terminal template replaced by the command to run.
+pub const COMMAND_PLACEHOLDER: &str = "{command}";
+/// Placeholder of the terminal template replaced by the name of the shortcut.
+pub const NAME_PLACEHOLDER: &str = "{name}";
+
+/// The layouts of tmux available for the windows of a workspace.
+pub const LAYOUTS: [&str; 5] = [
+    "even-horizontal",
+    "even-vertical",
+    "main-horizontal",
+    "main-vertical",
+    "tiled",
+];
+pub const DEFAULT_LAYOUT: &str = "tiled";
+/// The window of the panes of a new workspace
+pub const DEFAULT_WINDOW: &str = "main";
+
+/// The tmux server used by the tests, so they don't touch the sessions of the user.
+#[cfg(test)]
+const TMUX_SOCKET: Option<&str> = Some("myshortcuts-test");
+#[cfg(not(test))]
+const TMUX_SOCKET: Option<&str> = None;
+
+/// Where the command of a shortcut is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenMode {
+    /// The shell which launched the app
+    Current,
+    TmuxWindow,
+    TmuxSplit,
+    ScreenWindow,
+    /// The terminal emulator of `config.toml`
+    Terminal,
+}
+
+const OPEN_MODES: [(OpenMode, &str); 5] = [
+    (OpenMode::Current, "Current shell"),
+    (OpenMode::TmuxWindow, "New tmux window"),
+    (OpenMode::TmuxSplit, "Tmux split"),
+    (OpenMode::ScreenWindow, "New screen window"),
+    (OpenMode::Terminal, "New terminal"),
+];
+
+impl OpenMode {
+    pub fn get_name(&self) -> &'static str {
+        OPEN_MODES
+            .iter()
+            .find(|(mode, _)| mode == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    /// Get the modes usable from here : tmux and screen only when the app runs inside them.
+    pub fn available(settings: &Settings) -> Vec<OpenMode> {
+        OPEN_MODES
+            .iter()
+            .map(|(mode, _)| *mode)
+            .filter(|mode| match mode {
+                OpenMode::Current => true,
+                OpenMode::TmuxWindow | OpenMode::TmuxSplit => env::var_os("TMUX").is_some(),
+                OpenMode::ScreenWindow => env::var_os("STY").is_some(),
+                OpenMode::Terminal => settings.get_terminal().is_some(),
+            })
+            .collect()
+    }
+
+    /// Run `args` (the program and its arguments) in a new window named `name`.<br>
+    /// The app keeps running, `Current` is handled by the shell wrapper.
+    pub fn launch(&self, name: &str, args: &[String], settings: &Settings) -> Result<()> {
+        let mut command = match self {
+            OpenMode::Current => {
+                return Err(Error::Validation(String::from(
+                    "The current shell can't be launched",
+                )));
+            }
+            OpenMode::TmuxWindow => {
+                let mut command = tmux_command();
+                command.args(["new-window", "-n", name, "--"]).args(args);
+                command
+            }
+            OpenMode::TmuxSplit => {
+                let mut command = tmux_command();
+                command.args(["split-window", "-h", "--"]).args(args);
+                command
+            }
+            OpenMode::ScreenWindow => {
+                let mut command = Command::new("screen");
+                command.args(["-X", "screen", "-t", name]).args(args);
+                command
+            }
+            OpenMode::Terminal => {
+                let template = settings
+                    .get_terminal()
+                    .ok_or(Error::MissingField(vec![String::from("terminal")]))?;
+                let mut parts = expand_template(template, name, args).into_iter();
+                let program = parts.next().ok_or(Error::Validation(String::from(
+                    "The terminal of config.toml is empty",
+                )))?;
+                let mut command = Command::new(program);
+                command.args(parts);
+                // The terminal lives after the app, it isn't waited
+                return command
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map(|_| ())
+                    .map_err(|e| Error::io(format!("Failed to run '{}'", template), e));
+            }
+        };
+
+        let output = command
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| Error::io(format!("Failed to run {}", self.get_name()), e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(Error::command(format!(
+                "{} failed : {}",
+                self.get_name(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+}
+
+/// Open the workspace in a tmux session named like it, `commands` are the program and the
+/// arguments of each pane.<br>
+/// The session is reused when it's already open. Inside tmux the client switches to it,
+/// otherwise the name of the session to attach is returned.
+pub fn open_workspace(workspace: &Workspace, commands: &[Vec<String>]) -> Result<Option<String>> {
+    // tmux doesn't allow these characters in the name of a session
+    let session = workspace.get_name().replace([':', '.'], "_");
+    let target = format!("={}", session);
+
+    if tmux(&["has-session", "-t", &target]).is_err() {
+        let mut windows: Vec<&String> = Vec::new();
+        for (pane, args) in workspace.get_panes().iter().zip(commands) {
+            let window = format!("{}:{}", target, pane.get_window());
+            let mut command = if windows.is_empty() {
+                vec!["new-session", "-d", "-s", &session, "-n", pane.get_window()]
+            } else if windows.contains(&pane.get_window()) {
+                vec!["split-window", "-t", &window]
+            } else {
+                vec!["new-window", "-t", &target, "-n", pane.get_window()]
+            };
+            command.push("--");
+            command.extend(args.iter().map(|arg| arg.as_str()));
+            tmux(&command)?;
+            // Arrange the panes after each split so there is room for the next one
+            tmux(&["select-layout", "-t", &window, workspace.get_layout()])?;
+            windows.push(pane.get_window());
+        }
+    }
+
+    if env::var_os("TMUX").is_some() {
+        tmux(&["switch-client", "-t", &target])?;
+        Ok(None)
+    } else {
+        Ok(Some(target))
+    }
+}
+
+fn tmux_command() -> Command {
+    let mut command = Command::new("tmux");
+    if let Some(socket) = TMUX_SOCKET {
+        command.args(["-L", socket]);
+    }
+    command
+}
+
+fn tmux(args: &[&str]) -> Result<()> {
+    let output = tmux_command()
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| Error::io(String::from("Failed to run tmux"), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::command(format!(
+            "tmux {} failed : {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Split the terminal template on the spaces, `{command}` becomes the program and its
+/// arguments and `{name}` the name of the shortcut.
+fn expand_template(template: &str, name: &str, args: &[String]) -> Vec<String> {
+    let mut parts = Vec::new();
+    for part in template.split_whitespace() {
+        if part == COMMAND_PLACEHOLDER {
+            parts.extend(args.iter().cloned());
+        } else {
+            parts.push(part.replace(NAME_PLACEHOLDER, name));
+        }
+    }
+    if !template.contains(COMMAND_PLACEHOLDER) {
+        parts.extend(args.iter().cloned());
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::Pane;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| String::from(*v)).collect()
+    }
+
+    #[test]
+    fn expand_template_replaces_the_placeholders() {
+        assert_eq!(
+            expand_template(
+                "alacritty --title {name} -e {command}",
+                "prod-pg",
+                &args(&["bash", "-c", "psql -h db"])
+            ),
+            args(&[
+                "alacritty",
+                "--title",
+                "prod-pg",
+                "-e",
+                "bash",
+                "-c",
+                "psql -h db"
+            ])
+        );
+    }
+
+    #[test]
+    fn expand_template_keeps_the_name_inside_a_word() {
+        assert_eq!(
+            expand_template("kitty --title=MS:{name} {command}", "a", &args(&["top"])),
+            args(&["kitty", "--title=MS:a", "top"])
+        );
+    }
+
+    #[test]
+    fn expand_template_appends_the_command_without_placeholder() {
+        assert_eq!(
+            expand_template("  wezterm   start  ", "a", &args(&["bash", "-c", "ls"])),
+            args(&["wezterm", "start", "bash", "-c", "ls"])
+        );
+    }
+
+    #[test]
+    fn expand_template_of_an_empty_template() {
+        assert_eq!(expand_template("", "a", &args(&["ls"])), args(&["ls"]));
+        assert!(expand_template("", "a", &[]).is_empty());
+    }
+
+    /// Stops the tmux server of the tests, even when an assertion fails.
+    struct Server;
+
+    impl Server {
+        /// Start the server with a session, `None` when tmux isn't installed.
+        fn start() -> Option<Server> {
+            let status = tmux_command()
+                .args(["new-session", "-d", "-s", "main", "-n", "main"])
+                .stdin(Stdio::null())
+                .status()
+                .ok()?;
+            assert!(
+                status.success(),
+                "The tmux server of the tests didn't start"
+            );
+            Some(Server)
+        }
+
+        fn list(&self, args: &[&str]) -> Vec<String> {
+            let output = tmux_command().args(args).output().unwrap();
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = tmux(&["kill-server"]);
+        }
+    }
+
+    #[test]
+    fn open_in_tmux() {
+        let Some(server) = Server::start() else {
+            eprintln!("tmux isn't installed, the test is skipped");
+            return;
+        };
+        let settings = Settings::default();
+
+        OpenMode::TmuxWindow
+            .launch("prod-pg", &args(&["sleep", "30"]), &settings)
+            .unwrap();
+        assert!(
+            server
+                .list(&["list-windows", "-t", "=main", "-F", "#{window_name}"])
+                .contains(&String::from("prod-pg"))
+        );
+
+        let workspace = Workspace::from(
+            "incident.1",
+            DEFAULT_LAYOUT,
+            vec![
+                Pane::from("pg", "databases"),
+                Pane::from("redis", "databases"),
+                Pane::from("logs", "logs"),
+            ],
+        );
+        let commands = vec![
+            args(&["sleep", "30"]),
+            args(&["sleep", "31"]),
+            args(&["sleep", "32"]),
+        ];
+        let opened = open_workspace(&workspace, &commands);
+        // Inside tmux the client of the user can't switch to the server of the tests
+        if env::var_os("TMUX").is_none() {
+            assert_eq!(opened.unwrap(), Some(String::from("=incident_1")));
+        }
+        assert_eq!(
+            server.list(&["list-windows", "-t", "=incident_1", "-F", "#{window_name}"]),
+            args(&["databases", "logs"])
+        );
+        assert_eq!(
+            server
+                .list(&[
+                    "list-panes",
+                    "-s",
+                    "-t",
+                    "=incident_1",
+                    "-F",
+                    "#{window_name} #{pane_start_command}",
+                ])
+                .iter()
+                .map(|pane| pane.replace('"', ""))
+                .collect::<Vec<String>>(),
+            args(&["databases sleep 30", "databases sleep 31", "logs sleep 32"])
+        );
+
+        // The session is reused instead of being created twice
+        open_workspace(&workspace, &commands).ok();
+        assert_eq!(
+            server
+                .list(&["list-panes", "-s", "-t", "=incident_1", "-F", "#{pane_id}"])
+                .len(),
+            3
+        );
+    }
+}
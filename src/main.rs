@@ -1,54 +1,257 @@
 mod app;
+mod catalog;
+mod cli;
+mod config;
 mod database;
+mod editor;
+mod error;
+mod keymap;
+mod launcher;
+mod logs;
 mod objects;
+mod shell;
+mod theme;
 mod ui;
 mod utils;
 
-use app::main_app;
-use database::{DB_NAME, Database};
-use std::{fs::{self, OpenOptions}, io::Write};
-use utils::{Logs, get_folder_path};
+use app::{Outcome, main_app};
+use cli::{Command, HistoryFilter, USAGE, parse_args};
+use color_eyre::Report;
+use config::{CONFIG_NAME, Settings};
+use database::{
+    DB_NAME, DEFAULT_PROFILE, Database, get_database_path, get_history, get_profile_path,
+    purge_expired_trash, set_database_path,
+};
+use error::{Error, Result};
+use keymap::KEYMAP_NAME;
+use logs::Logs;
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+use theme::THEME_NAME;
+use utils::{
+    create_private_file, create_private_folder, format_date, get_config_path, get_data_path,
+    get_legacy_path, move_file,
+};
 
 fn main() {
-    if let Ok(mut path) = get_folder_path() {
-        if !fs::exists(&path).unwrap_or(true) {
-            match fs::create_dir(&path) {
-                Ok(_) => {}
-                Err(error) => Logs::write(format!("\n{:?}", error)),
+    if let Err(error) = color_eyre::install() {
+        eprintln!("ERROR : {}", error);
+    }
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Init(shell)) => {
+            let executable = env::current_exe()
+                .map(|path| path.display().to_string())
+                .unwrap_or(String::from("myshortcuts"));
+            print!("{}", shell.wrapper(&executable));
+            if let Some(widget) = shell.widget(&executable) {
+                print!("{}", widget);
             }
+            return;
         }
-
-        path.push(DB_NAME);
-
-        if !fs::exists(path).unwrap_or(true) {
-            if let Err(error) = Database::init() {
-                panic!("{error}")
-            } else {
-                let _init = Database::query_write("
-                    insert into shortcuts values ('c6', '127.0.0.1', 'userA', 'my_db', 'password', 'Neo4j');
-                ");
+        Ok(Command::History(filter)) => {
+            Logs::init(false);
+            if let Err(error) = select_database(filter.get_database(), filter.get_profile())
+                .and_then(|_| init_resources())
+                .and_then(|_| print_history(&filter))
+            {
+                fail("main.rs - print_history()", error);
             }
+            return;
+        }
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("ERROR : {}\n\n{}", error, USAGE);
+            process::exit(2);
         }
+    };
+    let shell = options.get_shell();
+    let output = options.get_output();
+
+    Logs::init(options.is_verbose());
+    Logs::debug("main.rs - main()", format!("Started with {:?}", options));
+    let profile = match select_database(options.get_database(), options.get_profile()) {
+        Ok(profile) => profile,
+        Err(error) => fail("main.rs - select_database()", error),
+    };
+    if let Err(error) = init_resources() {
+        fail("main.rs - init_resources()", error);
+    }
+
+    // Created before the TUI so the user doesn't choose a command that can't be written
+    let mut file = if options.is_print() || options.is_exec() {
+        None
     } else {
-        println!("ERROR : Failed to get the folder path where the script is.")
-    }
-
-    match main_app() {
-        Ok(mut command) => {
-            match OpenOptions::new().create(true).truncate(true).write(true).open("/tmp/myshortcuts_command.sh") {
-                Ok(mut file) => {
-                    if command.is_empty() {
-                        command.push_str("echo Good bye dear user !");
-                    }
-                    if let Err(error) = file.write_all(command.as_bytes()) {
-                        Logs::write(format!("\n{}", error));
-                    }
-                }
-                Err(error) => {
-                    Logs::write(format!("\n{}", error));
-                }
+        match create_private_file(&output) {
+            Ok(file) => Some(file),
+            Err(error) => fail("main.rs - create_private_file()", error),
+        }
+    };
+
+    // The shell only reads the file when a command was chosen
+    let discard = |file: &Option<fs::File>| {
+        if file.is_some() {
+            let _ = fs::remove_file(&output);
+        }
+    };
+    match main_app(shell, options.is_print(), options.is_exec(), profile) {
+        Ok(Outcome::Exited(code)) => process::exit(code),
+        // Nothing was chosen, the shell doesn't run anything
+        Ok(Outcome::Command(command)) if command.is_empty() => {
+            discard(&file);
+            process::exit(1)
+        }
+        Ok(Outcome::Command(command)) => {
+            let result = match file.as_mut() {
+                Some(file) => file.write_all(command.as_bytes()),
+                None => writeln!(io::stdout(), "{}", command),
+            };
+            if let Err(error) = result {
+                discard(&file);
+                fail("main.rs - write_all()", Error::from(error));
             }
-        },
-        Err(error) => Logs::write(format!("ERROR with the function mainApp :\n{error}")),
+        }
+        Err(error) => {
+            discard(&file);
+            fail("main.rs - main_app()", Error::from(error))
+        }
+    }
+}
+
+/// Use the database of `--db`, otherwise the one of the profile (the default one without `--profile`).<br>
+/// Get the name of the active profile, there is none with `--db`.
+fn select_database(database: Option<PathBuf>, profile: Option<&str>) -> Result<Option<String>> {
+    if let Some(database) = database {
+        set_database_path(database);
+        return Ok(None);
+    }
+    let profile = profile.unwrap_or(DEFAULT_PROFILE);
+    set_database_path(get_profile_path(profile)?);
+    Ok(Some(String::from(profile)))
+}
+
+/// Create the data folder and the database, then apply the migrations.
+fn init_resources() -> Result<()> {
+    let data = get_data_path()?;
+    migrate_legacy_resources(&data)?;
+    // The logs are written in the data folder even when the database is elsewhere
+    create_private_folder(&data)?;
+
+    let path = get_database_path()?;
+    if let Some(folder) = path.parent().filter(|f| !f.as_os_str().is_empty()) {
+        create_private_folder(folder)?;
+    }
+
+    if !fs::exists(&path).unwrap_or(true) {
+        Database::init(&path)?;
+        Database::query_write(
+            "insert into shortcuts values ('Default0', 'echo Welcome on MyShortcuts !', 'Custom');",
+        )?;
+    }
+
+    Database::migrate(&path)?;
+    let settings = Settings::load();
+    if let Err(error) = purge_expired_trash(settings.get_trash_retention_days()) {
+        Logs::error("main.rs - purge_expired_trash()", error);
+    }
+    Ok(())
+}
+
+/// Move the files of `myshortcuts_resources`, the folder next to the executable used by the
+/// previous versions, to the data and the configuration folders.<br>
+/// It's done once : nothing is moved when the data folder already holds a database.
+fn migrate_legacy_resources(data: &Path) -> Result<()> {
+    let Ok(legacy) = get_legacy_path() else {
+        return Ok(());
+    };
+    if !legacy.join(DB_NAME).is_file() || data.join(DB_NAME).exists() {
+        return Ok(());
+    }
+    let config = get_config_path()?;
+    create_private_folder(data)?;
+    create_private_folder(&config)?;
+
+    let mut files = fs::read_dir(&legacy)
+        .map_err(|e| Error::io(format!("Failed to read '{}'", legacy.display()), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    // The database is moved last, so the migration is done again if another file failed
+    files.sort_by_key(|path| path.ends_with(DB_NAME));
+    for from in files {
+        let Some(name) = from.file_name() else {
+            continue;
+        };
+        let folder = if [CONFIG_NAME, KEYMAP_NAME, THEME_NAME]
+            .iter()
+            .any(|n| name == *n)
+        {
+            &config
+        } else {
+            data
+        };
+        let to = folder.join(name);
+        if !to.exists() {
+            move_file(&from, &to)?;
+        }
+    }
+    // Only removed once it's empty
+    let _ = fs::remove_dir(&legacy);
+    Logs::info(
+        "main.rs - migrate_legacy_resources()",
+        format!(
+            "Moved '{}' to '{}' and '{}'",
+            legacy.display(),
+            data.display(),
+            config.display()
+        ),
+    );
+    Ok(())
+}
+
+/// Log the error and report it on the terminal with its causes, then exit.
+fn fail(origin: &str, error: Error) -> ! {
+    Logs::error(origin, &error);
+    eprintln!("{:?}", Report::new(error));
+    process::exit(1);
+}
+
+/// Print the entries of the history separated by tabs, the most recent first.
+fn print_history(filter: &HistoryFilter) -> Result<()> {
+    let history = get_history(
+        filter.get_shortcut(),
+        filter.get_since(),
+        filter.get_until(),
+        filter.get_limit(),
+    )?;
+    let mut stdout = io::stdout().lock();
+    let mut lines = vec![String::from(
+        "DATE (UTC)\tUSER\tMODE\tEXIT\tKIND\tNAME\tCOMMAND",
+    )];
+    lines.extend(history.iter().map(|entry| {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            format_date(entry.get_opened_at()),
+            entry.get_user(),
+            entry.get_mode(),
+            entry
+                .get_exit_code()
+                .map_or(String::from("-"), |code| code.to_string()),
+            entry.get_kind(),
+            entry.get_name(),
+            entry.get_command().replace('\n', " ")
+        )
+    }));
+    for line in lines {
+        writeln!(stdout, "{}", line)?;
     }
+    Ok(())
 }
@@ -1,6 +1,12 @@
 use ratatui::widgets::TableState;
 use tui_input::Input;
 
+use crate::error::{Error, Result};
+use crate::utils::{current_user, decode_config, encode_value, unix_now};
+
+/// Stored in place of the value of a property that was never filled.
+pub const UNSET: &str = "\\u";
+
 #[derive(Debug, Clone)]
 pub enum State {
     Selected(TableState),
@@ -8,16 +14,62 @@ pub enum State {
     Editing(TableState, Input),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Shortcut {
     name: String,
     kind: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Configuration {
     value: String,
     kind: String,
+    /// The property was never filled, which is different from an empty value
+    unset: bool,
+    /// The value comes from the shared catalog, the property is unset in the database
+    inherited: bool,
+}
+
+/// A shortcut opened by a user, kept in the history.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    /// Unix timestamp of the opening
+    opened_at: i64,
+    user: String,
+    name: String,
+    kind: String,
+    /// Where the command was run, like `Current shell` or `New tmux window`
+    mode: String,
+    /// The command with its secrets masked
+    command: String,
+    /// Only known when the app runs the command itself
+    exit_code: Option<i32>,
+}
+
+#[derive(Debug)]
+pub struct TrashEntry {
+    id: i64,
+    name: String,
+    kind: String,
+    /// Unix timestamp of the deletion
+    deleted_at: i64,
+}
+
+/// Shortcuts opened together in the panes of a tmux session.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    name: String,
+    /// The tmux layout of each window, like `tiled`
+    layout: String,
+    panes: Vec<Pane>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pane {
+    /// The name of the shortcut opened in the pane
+    shortcut: String,
+    /// The name of the tmux window holding the pane
+    window: String,
 }
 
 impl Shortcut {
@@ -28,7 +80,6 @@ impl Shortcut {
         }
     }
 
-    #[allow(unused)]
     pub fn from(name: &str, kind: &str) -> Self {
         Shortcut {
             name: String::from(name),
@@ -36,7 +87,7 @@ impl Shortcut {
         }
     }
 
-    pub fn parse(value: &str) -> Result<Self, String> {
+    pub fn parse(value: &str) -> Result<Self> {
         let vector = value.split(";").collect::<Vec<&str>>();
         if let (Some(name), Some(kind)) = (vector.first(), vector.get(1)) {
             Ok(Shortcut {
@@ -44,10 +95,10 @@ impl Shortcut {
                 kind: String::from(*kind),
             })
         } else {
-            Err(format!(
+            Err(Error::Parse(format!(
                 "ERROR : when try to parse the following shortcut : '{}'",
                 value
-            ))
+            )))
         }
     }
 
@@ -69,6 +120,8 @@ impl Configuration {
         Configuration {
             value: String::from("echo Welcome on MyShortcuts"),
             kind: String::from("DefaultProperty"),
+            unset: false,
+            inherited: false,
         }
     }
 
@@ -76,6 +129,54 @@ impl Configuration {
         Configuration {
             value: String::from(value),
             kind: String::from(kind),
+            unset: false,
+            inherited: false,
+        }
+    }
+
+    pub fn unset(kind: &str) -> Self {
+        Configuration {
+            value: String::new(),
+            kind: String::from(kind),
+            unset: true,
+            inherited: false,
+        }
+    }
+
+    pub fn is_unset(&self) -> bool {
+        self.unset
+    }
+
+    pub fn is_inherited(&self) -> bool {
+        self.inherited
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.value = String::from(value);
+        self.unset = false;
+        self.inherited = false;
+    }
+
+    /// Show the value of the catalog, the property stays unset in the database.
+    pub fn inherit(&mut self, value: &str) {
+        self.value = String::from(value);
+        self.unset = false;
+        self.inherited = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.unset = true;
+        self.inherited = false;
+    }
+
+    /// Format the value to be stored in the database, see `decode_config`.<br>
+    /// An inherited value is stored unset, so the catalog can still change it.
+    pub fn encode(&self) -> String {
+        if self.unset || self.inherited {
+            String::from(UNSET)
+        } else {
+            encode_value(&self.value)
         }
     }
 
@@ -83,11 +184,174 @@ impl Configuration {
         &self.value
     }
 
-    pub fn get_mut_value(&mut self) -> &mut String {
-        &mut self.value
+    pub fn get_kind(&self) -> &String {
+        &self.kind
+    }
+}
+
+impl Workspace {
+    pub fn from(name: &str, layout: &str, panes: Vec<Pane>) -> Self {
+        Workspace {
+            name: String::from(name),
+            layout: String::from(layout),
+            panes,
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_layout(&self) -> &String {
+        &self.layout
+    }
+
+    pub fn get_panes(&self) -> &Vec<Pane> {
+        &self.panes
+    }
+}
+
+impl Pane {
+    pub fn from(shortcut: &str, window: &str) -> Self {
+        Pane {
+            shortcut: String::from(shortcut),
+            window: String::from(window),
+        }
+    }
+
+    /// Parse a line formatted as `shortcut;window;`
+    pub fn parse(value: &str) -> Result<Self> {
+        let vector = value.split(";").collect::<Vec<&str>>();
+        if let (Some(shortcut), Some(window)) = (vector.first(), vector.get(1)) {
+            Ok(Pane::from(shortcut, window))
+        } else {
+            Err(Error::Parse(format!(
+                "ERROR : when try to parse the following pane : '{}'",
+                value
+            )))
+        }
+    }
+
+    pub fn get_shortcut(&self) -> &String {
+        &self.shortcut
+    }
+
+    pub fn get_window(&self) -> &String {
+        &self.window
+    }
+}
+
+impl HistoryEntry {
+    /// Create the entry of a shortcut opened now by the current user.
+    pub fn from(name: &str, kind: &str, mode: &str, command: &str) -> Self {
+        HistoryEntry {
+            opened_at: unix_now(),
+            user: current_user(),
+            name: String::from(name),
+            kind: String::from(kind),
+            mode: String::from(mode),
+            command: String::from(command),
+            exit_code: None,
+        }
+    }
+
+    /// Parse a line formatted as `opened_at;exit_code;user;name;kind;mode;command;`,
+    /// every text is encoded like the configurations.
+    pub fn parse(value: &str) -> Result<Self> {
+        let fields = decode_config(value)
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect::<Vec<String>>();
+        match &fields[..] {
+            [opened_at, exit_code, user, name, kind, mode, command] => Ok(HistoryEntry {
+                opened_at: opened_at.parse::<i64>().unwrap_or(0),
+                user: String::clone(user),
+                name: String::clone(name),
+                kind: String::clone(kind),
+                mode: String::clone(mode),
+                command: String::clone(command),
+                exit_code: exit_code.parse::<i32>().ok(),
+            }),
+            _ => Err(Error::Parse(format!(
+                "ERROR : when try to parse the following history entry : '{}'",
+                value
+            ))),
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: &str) {
+        self.mode = String::from(mode);
+    }
+
+    pub fn set_exit_code(&mut self, exit_code: i32) {
+        self.exit_code = Some(exit_code);
+    }
+
+    pub fn get_opened_at(&self) -> i64 {
+        self.opened_at
+    }
+
+    pub fn get_user(&self) -> &String {
+        &self.user
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
     }
 
     pub fn get_kind(&self) -> &String {
         &self.kind
     }
+
+    pub fn get_mode(&self) -> &String {
+        &self.mode
+    }
+
+    pub fn get_command(&self) -> &String {
+        &self.command
+    }
+
+    pub fn get_exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+}
+
+impl TrashEntry {
+    /// Parse a line formatted as `id;deleted_at;kind;name;`
+    pub fn parse(value: &str) -> Result<Self> {
+        let vector = value.splitn(4, ";").collect::<Vec<&str>>();
+        match (
+            vector.first().and_then(|id| id.parse::<i64>().ok()),
+            vector.get(1).and_then(|date| date.parse::<i64>().ok()),
+            vector.get(2),
+            vector.get(3),
+        ) {
+            (Some(id), Some(deleted_at), Some(kind), Some(name)) => Ok(TrashEntry {
+                id,
+                name: String::from(name.strip_suffix(";").unwrap_or(name)),
+                kind: String::from(*kind),
+                deleted_at,
+            }),
+            _ => Err(Error::Parse(format!(
+                "ERROR : when try to parse the following trash entry : '{}'",
+                value
+            ))),
+        }
+    }
+
+    pub fn get_id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> &String {
+        &self.kind
+    }
+
+    pub fn get_deleted_at(&self) -> i64 {
+        self.deleted_at
+    }
 }
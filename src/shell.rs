@@ -0,0 +1,576 @@
+use std::env;
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::utils::mask_secrets;
+
+/// The shells supported by `myshortcuts init`, each one gets the command in its own syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+    Powershell,
+}
+
+/// A command with the environment variables it needs and what is sent to its input.<br>
+/// The builders of `utils` create it, it's rendered in the syntax of the user's shell.
+#[derive(Debug, Clone)]
+pub struct ShellCommand {
+    env: Vec<(String, String)>,
+    body: Body,
+    input: Option<Input>,
+}
+
+#[derive(Debug, Clone)]
+enum Body {
+    /// The program and its arguments, each one is quoted for the shell
+    Args(Vec<String>),
+    /// A command written by the user, given as is to the shell
+    Script(String),
+}
+
+#[derive(Debug, Clone)]
+enum Input {
+    /// A file redirected to the standard input of the command
+    File(String),
+    /// A text piped to the standard input of the command
+    Text(String),
+}
+
+const SHELLS: [(Shell, &[&str]); 5] = [
+    (Shell::Bash, &["bash", "sh"]),
+    (Shell::Zsh, &["zsh"]),
+    (Shell::Fish, &["fish"]),
+    (Shell::Nu, &["nu", "nushell"]),
+    (Shell::Powershell, &["powershell", "pwsh"]),
+];
+
+impl Shell {
+    pub fn parse(name: &str) -> Result<Self> {
+        SHELLS
+            .iter()
+            .find(|(_, names)| names.contains(&name.to_lowercase().as_str()))
+            .map(|(shell, _)| *shell)
+            .ok_or(Error::Parse(format!(
+                "Unknown shell '{}', expected bash, zsh, fish, nu or powershell",
+                name
+            )))
+    }
+
+    /// Guess the shell from `$SHELL`, bash when it's unknown.
+    pub fn detect() -> Self {
+        env::var("SHELL")
+            .ok()
+            .and_then(|path| {
+                path.rsplit('/')
+                    .next()
+                    .and_then(|name| Shell::parse(name).ok())
+            })
+            .unwrap_or(Shell::Bash)
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        SHELLS
+            .iter()
+            .find(|(shell, _)| shell == self)
+            .map(|(_, names)| names[0])
+            .unwrap_or_default()
+    }
+
+    /// Get the program and the arguments running `command` in a new shell.
+    pub fn invocation(&self, command: &str) -> Vec<String> {
+        let (program, flag) = match self {
+            Shell::Powershell => ("pwsh", "-Command"),
+            _ => (self.get_name(), "-c"),
+        };
+        vec![
+            String::from(program),
+            String::from(flag),
+            String::from(command),
+        ]
+    }
+
+    /// The extension of the scripts, PowerShell only runs the `.ps1` files.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            Shell::Bash | Shell::Zsh => "sh",
+            Shell::Fish => "fish",
+            Shell::Nu => "nu",
+            Shell::Powershell => "ps1",
+        }
+    }
+
+    /// Quote a value so the shell doesn't interpret it.
+    pub fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", value.replace('\'', "'\\''")),
+            Shell::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+            Shell::Nu => format!(
+                "\"{}\"",
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            ),
+            Shell::Powershell => format!("'{}'", value.replace('\'', "''")),
+        }
+    }
+
+    /// Get the function to add to the configuration of the shell.<br>
+    /// It runs `executable` with an output file unique to the shell's process,
+    /// runs the command chosen by the user then deletes the file.
+    pub fn wrapper(&self, executable: &str) -> String {
+        let executable = self.quote(executable);
+        match self {
+            Shell::Bash | Shell::Zsh => format!(
+                "myshortcuts() {{
+    local output=\"${{XDG_RUNTIME_DIR:-${{TMPDIR:-/tmp}}}}/myshortcuts_command.$$.sh\"
+    {} --shell {} --output \"$output\" \"$@\" && source \"$output\"
+    local code=$?
+    rm -f \"$output\"
+    return $code
+}}
+",
+                executable,
+                self.get_name()
+            ),
+            Shell::Fish => format!(
+                "function myshortcuts
+    set -l dir /tmp
+    set -q XDG_RUNTIME_DIR; and set dir $XDG_RUNTIME_DIR
+    set -l output $dir/myshortcuts_command.$fish_pid.fish
+    {} --shell fish --output $output $argv; and source $output
+    set -l code $status
+    rm -f $output
+    return $code
+end
+",
+                executable
+            ),
+            Shell::Nu => format!(
+                "def --env myshortcuts [...args] {{
+    let output = ($env.XDG_RUNTIME_DIR? | default \"/tmp\" | path join $\"myshortcuts_command.($nu.pid).nu\")
+    try {{
+        ^{} --shell nu --output $output ...$args
+        nu -c (open --raw $output)
+    }}
+    rm -f $output
+}}
+",
+                executable
+            ),
+            Shell::Powershell => format!(
+                "function myshortcuts {{
+    $dir = if ($env:XDG_RUNTIME_DIR) {{ $env:XDG_RUNTIME_DIR }} else {{ [IO.Path]::GetTempPath() }}
+    $output = Join-Path $dir \"myshortcuts_command.$PID.ps1\"
+    & {} --shell powershell --output $output @args
+    if ($LASTEXITCODE -eq 0) {{ . $output }}
+    Remove-Item -Force -ErrorAction SilentlyContinue $output
+}}
+",
+                executable
+            ),
+        }
+    }
+
+    /// Get the widget launching the app on `Alt-m` and inserting the command in the
+    /// command line, so it can be edited before being run.<br>
+    /// Nushell and PowerShell don't have one.
+    pub fn widget(&self, executable: &str) -> Option<String> {
+        let executable = self.quote(executable);
+        match self {
+            Shell::Bash => Some(format!(
+                "__myshortcuts_widget() {{
+    local command
+    command=\"$({} --shell bash --print)\" || return
+    READLINE_LINE=\"${{READLINE_LINE:0:$READLINE_POINT}}$command${{READLINE_LINE:$READLINE_POINT}}\"
+    READLINE_POINT=$((READLINE_POINT + ${{#command}}))
+}}
+if [[ $- == *i* ]]; then
+    bind -x '\"\\em\": __myshortcuts_widget'
+fi
+",
+                executable
+            )),
+            Shell::Zsh => Some(format!(
+                "__myshortcuts_widget() {{
+    local command
+    command=\"$({} --shell zsh --print < /dev/tty)\" && LBUFFER+=\"$command\"
+    zle reset-prompt
+}}
+zle -N __myshortcuts_widget
+bindkey '\\em' __myshortcuts_widget
+",
+                executable
+            )),
+            Shell::Fish => Some(format!(
+                "function __myshortcuts_widget
+    set -l command ({} --shell fish --print | string collect)
+    and commandline -i -- $command
+    commandline -f repaint
+end
+bind \\em __myshortcuts_widget
+",
+                executable
+            )),
+            Shell::Nu | Shell::Powershell => None,
+        }
+    }
+
+    /// Quote an argument only when the shell would interpret it, so the usual
+    /// hosts, ports and paths stay readable.
+    pub fn quote_arg(&self, value: &str) -> String {
+        let safe = |c: char| {
+            c.is_ascii_alphanumeric()
+                || "_-./:=%+~@".contains(c)
+                || (c == ',' && *self != Shell::Powershell)
+        };
+        // PowerShell splats the variables starting with '@'
+        let splat = *self == Shell::Powershell && value.starts_with('@');
+        // Zsh replaces a word starting with '=' by the path of the program
+        let equals = value.starts_with('=');
+        if !value.is_empty() && !splat && !equals && value.chars().all(safe) {
+            String::from(value)
+        } else {
+            self.quote(value)
+        }
+    }
+
+    /// Write the command in the syntax of the shell.<br>
+    /// The environment variables are only given to the command, not kept in the user's shell.
+    pub fn render(&self, command: &ShellCommand) -> String {
+        let mut body = match &command.body {
+            Body::Args(args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.quote_arg(arg))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                match self {
+                    // A quoted program is a string for PowerShell and Nushell, not a command
+                    Shell::Powershell if args.starts_with('\'') => format!("& {}", args),
+                    Shell::Nu if args.starts_with('"') => format!("^{}", args),
+                    _ => args,
+                }
+            }
+            Body::Script(script) => String::clone(script),
+        };
+        if !command.env.is_empty() {
+            let env = command
+                .env
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, self.quote(value)))
+                .collect::<Vec<String>>()
+                .join(" ");
+            match self {
+                Shell::Bash | Shell::Zsh => body = format!("{} {}", env, body),
+                Shell::Fish => body = format!("env {} {}", env, body),
+                Shell::Nu | Shell::Powershell => {}
+            }
+        }
+        let pipeline = match (&command.input, self) {
+            (Some(Input::File(path)), Shell::Nu) => {
+                format!("open --raw {} | {}", self.quote_arg(path), body)
+            }
+            (Some(Input::File(path)), Shell::Powershell) => {
+                format!("Get-Content {} | {}", self.quote_arg(path), body)
+            }
+            (Some(Input::File(path)), _) => format!("{} < {}", body, self.quote_arg(path)),
+            (Some(Input::Text(text)), _) => format!("echo {} | {}", self.quote_arg(text), body),
+            (None, _) => body,
+        };
+        if command.env.is_empty() {
+            return pipeline;
+        }
+        match self {
+            Shell::Nu => format!(
+                "with-env {{{}}} {{ {} }}",
+                command
+                    .env
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, self.quote(value)))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                pipeline
+            ),
+            Shell::Powershell => format!(
+                "try {{ {} {} }} finally {{ Remove-Item {} }}",
+                command
+                    .env
+                    .iter()
+                    .map(|(name, value)| format!("$env:{} = {};", name, self.quote(value)))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                pipeline,
+                command
+                    .env
+                    .iter()
+                    .map(|(name, _)| format!("Env:{}", name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            _ => pipeline,
+        }
+    }
+}
+
+impl ShellCommand {
+    /// A program whose arguments are added with `arg`.
+    pub fn new(program: &str) -> Self {
+        ShellCommand {
+            env: vec![],
+            body: Body::Args(vec![String::from(program)]),
+            input: None,
+        }
+    }
+
+    /// A command written by the user in the syntax of their shell.
+    pub fn script(script: String) -> Self {
+        ShellCommand {
+            env: vec![],
+            body: Body::Script(script),
+            input: None,
+        }
+    }
+
+    /// Add an argument, it's quoted when the command is rendered.
+    pub fn arg(mut self, value: &str) -> Self {
+        if let Body::Args(args) = &mut self.body {
+            args.push(String::from(value));
+        }
+        self
+    }
+
+    /// Add a flag followed by its value.
+    pub fn flag(self, flag: &str, value: &str) -> Self {
+        self.arg(flag).arg(value)
+    }
+
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.env.push((String::from(name), String::from(value)));
+        self
+    }
+
+    pub fn with_input(mut self, path: &str) -> Self {
+        self.input = Some(Input::File(String::from(path)));
+        self
+    }
+
+    /// Send a text to the standard input of the command, like `echo exit | sqlplus`.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.input = Some(Input::Text(String::from(text)));
+        self
+    }
+
+    /// Get a copy where the secrets are replaced by `****` in the values and the arguments.<br>
+    /// It's done before the rendering, which can quote the secrets beyond recognition.
+    pub fn mask(&self, secrets: &[&String]) -> Self {
+        let mut masked = self.clone();
+        for (_, value) in masked.env.iter_mut() {
+            *value = mask_secrets(value, secrets);
+        }
+        match &mut masked.body {
+            Body::Args(args) => args
+                .iter_mut()
+                .for_each(|arg| *arg = mask_secrets(arg, secrets)),
+            Body::Script(script) => *script = mask_secrets(script, secrets),
+        }
+        masked
+    }
+
+    /// Run another program with the same arguments.
+    pub fn replace_program(&mut self, program: &str) {
+        if let Body::Args(args) = &mut self.body
+            && let Some(first) = args.first_mut()
+        {
+            *first = String::from(program);
+        }
+    }
+}
+
+/// The command as it's written for bash, used for the preview.
+impl fmt::Display for ShellCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Shell::Bash.render(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    const VALUES: [&str; 9] = [
+        "it's",
+        "say \"hi\"",
+        "C:\\dir",
+        "$HOME",
+        "a b",
+        "a\nb",
+        "=cmd",
+        "@args",
+        "a,b",
+    ];
+
+    fn quote_all(shell: Shell) -> Vec<String> {
+        VALUES.iter().map(|value| shell.quote_arg(value)).collect()
+    }
+
+    #[test]
+    fn quote_for_bash_and_zsh() {
+        let expected = [
+            "'it'\\''s'",
+            "'say \"hi\"'",
+            "'C:\\dir'",
+            "'$HOME'",
+            "'a b'",
+            "'a\nb'",
+            "'=cmd'",
+            "@args",
+            "a,b",
+        ];
+        assert_eq!(quote_all(Shell::Bash), expected);
+        assert_eq!(quote_all(Shell::Zsh), expected);
+    }
+
+    #[test]
+    fn quote_for_fish() {
+        assert_eq!(
+            quote_all(Shell::Fish),
+            [
+                "'it\\'s'",
+                "'say \"hi\"'",
+                "'C:\\\\dir'",
+                "'$HOME'",
+                "'a b'",
+                "'a\nb'",
+                "'=cmd'",
+                "@args",
+                "a,b",
+            ]
+        );
+    }
+
+    #[test]
+    fn quote_for_nu() {
+        assert_eq!(
+            quote_all(Shell::Nu),
+            [
+                "\"it's\"",
+                "\"say \\\"hi\\\"\"",
+                "\"C:\\\\dir\"",
+                "\"$HOME\"",
+                "\"a b\"",
+                "\"a\\nb\"",
+                "\"=cmd\"",
+                "@args",
+                "a,b",
+            ]
+        );
+    }
+
+    #[test]
+    fn quote_for_powershell() {
+        assert_eq!(
+            quote_all(Shell::Powershell),
+            [
+                "'it''s'",
+                "'say \"hi\"'",
+                "'C:\\dir'",
+                "'$HOME'",
+                "'a b'",
+                "'a\nb'",
+                "'=cmd'",
+                "'@args'",
+                "'a,b'",
+            ]
+        );
+    }
+
+    #[test]
+    fn render_the_env_for_the_command_only() {
+        let command = ShellCommand::new("psql")
+            .flag("-h", "db")
+            .with_env("PGPASSWORD", "it's")
+            .with_input("/tmp/a b.sql");
+        assert_eq!(
+            Shell::Bash.render(&command),
+            "PGPASSWORD='it'\\''s' psql -h db < '/tmp/a b.sql'"
+        );
+        assert_eq!(
+            Shell::Fish.render(&command),
+            "env PGPASSWORD='it\\'s' psql -h db < '/tmp/a b.sql'"
+        );
+        assert_eq!(
+            Shell::Nu.render(&command),
+            "with-env {PGPASSWORD: \"it's\"} { open --raw \"/tmp/a b.sql\" | psql -h db }"
+        );
+        assert_eq!(
+            Shell::Powershell.render(&command),
+            "try { $env:PGPASSWORD = 'it''s'; Get-Content '/tmp/a b.sql' | psql -h db } \
+            finally { Remove-Item Env:PGPASSWORD }"
+        );
+    }
+
+    #[test]
+    fn render_a_quoted_program() {
+        let command = ShellCommand::new("/opt/my tools/db").with_text("exit");
+        assert_eq!(
+            Shell::Bash.render(&command),
+            "echo exit | '/opt/my tools/db'"
+        );
+        assert_eq!(
+            Shell::Nu.render(&command),
+            "echo exit | ^\"/opt/my tools/db\""
+        );
+        assert_eq!(
+            Shell::Powershell.render(&command),
+            "echo exit | & '/opt/my tools/db'"
+        );
+    }
+
+    #[test]
+    fn bash_gets_the_arguments_back() {
+        let command = VALUES.iter().fold(
+            ShellCommand::new("printf").arg("%s\\0"),
+            |command, value| command.arg(value),
+        );
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(Shell::Bash.render(&command))
+            .output()
+            .unwrap();
+        let output = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            output
+                .strip_suffix('\0')
+                .unwrap()
+                .split('\0')
+                .collect::<Vec<_>>(),
+            VALUES
+        );
+    }
+
+    #[test]
+    fn bash_gets_the_env_back() {
+        for value in VALUES {
+            let command = ShellCommand::new("printenv")
+                .arg("MYSHORTCUTS_TEST")
+                .with_env("MYSHORTCUTS_TEST", value);
+            let output = Command::new("bash")
+                .arg("-c")
+                .arg(format!(
+                    "{}; printenv MYSHORTCUTS_TEST || echo unset",
+                    Shell::Bash.render(&command)
+                ))
+                .output()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8(output.stdout).unwrap(),
+                format!("{}\nunset\n", value)
+            );
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use toml::Table;
+
+use crate::error::{Error, Result};
+use crate::logs::Logs;
+use crate::utils::get_config_path;
+
+pub const THEME_NAME: &str = "theme.toml";
+
+/// Colors used to render every widget.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub font: Color,
+    pub header: Color,
+    pub row_selected: Color,
+    pub column_selected: Color,
+    pub cell_selected: Color,
+    pub row_was_selected: Color,
+    pub column_was_selected: Color,
+    pub cell_editing: Color,
+    pub problem: Color,
+    pub hint: Color,
+    /// The keys shown in the help
+    pub key: Color,
+}
+
+/// The name of each color in the theme file.
+const COLORS: [&str; 12] = [
+    "background",
+    "font",
+    "header",
+    "row_selected",
+    "column_selected",
+    "cell_selected",
+    "row_was_selected",
+    "column_was_selected",
+    "cell_editing",
+    "problem",
+    "hint",
+    "key",
+];
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            name: String::from("dark"),
+            background: Color::Black,
+            font: Color::Rgb(227, 151, 143),
+            header: Color::Rgb(218, 93, 72),
+            row_selected: Color::Rgb(155, 175, 223),
+            column_selected: Color::Rgb(101, 175, 223),
+            cell_selected: Color::Rgb(155, 175, 223),
+            row_was_selected: Color::Rgb(117, 146, 206),
+            column_was_selected: Color::Rgb(117, 146, 206),
+            cell_editing: Color::Rgb(151, 192, 80),
+            problem: Color::Rgb(230, 80, 80),
+            hint: Color::DarkGray,
+            key: Color::LightCyan,
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            name: String::from("light"),
+            background: Color::Rgb(250, 250, 247),
+            font: Color::Rgb(88, 40, 34),
+            header: Color::Rgb(176, 52, 31),
+            row_selected: Color::Rgb(32, 78, 160),
+            column_selected: Color::Rgb(20, 100, 150),
+            cell_selected: Color::Rgb(32, 78, 160),
+            row_was_selected: Color::Rgb(92, 112, 160),
+            column_was_selected: Color::Rgb(92, 112, 160),
+            cell_editing: Color::Rgb(44, 120, 40),
+            problem: Color::Rgb(190, 30, 30),
+            hint: Color::Rgb(140, 140, 140),
+            key: Color::Rgb(0, 95, 135),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Theme {
+            name: String::from("high-contrast"),
+            background: Color::Black,
+            font: Color::White,
+            header: Color::Yellow,
+            row_selected: Color::LightCyan,
+            column_selected: Color::LightCyan,
+            cell_selected: Color::LightYellow,
+            row_was_selected: Color::Cyan,
+            column_was_selected: Color::Cyan,
+            cell_editing: Color::LightGreen,
+            problem: Color::LightRed,
+            hint: Color::Gray,
+            key: Color::LightYellow,
+        }
+    }
+
+    /// Only use the 16 colors of the terminal palette
+    pub fn ansi() -> Self {
+        Theme {
+            name: String::from("16-color"),
+            background: Color::Reset,
+            font: Color::Reset,
+            header: Color::Red,
+            row_selected: Color::LightBlue,
+            column_selected: Color::Cyan,
+            cell_selected: Color::LightBlue,
+            row_was_selected: Color::Blue,
+            column_was_selected: Color::Blue,
+            cell_editing: Color::Green,
+            problem: Color::LightRed,
+            hint: Color::DarkGray,
+            key: Color::Cyan,
+        }
+    }
+
+    /// Used when the `NO_COLOR` environment variable is set, the selection is still reversed or bold
+    pub fn no_color() -> Self {
+        Theme {
+            name: String::from("no-color"),
+            background: Color::Reset,
+            font: Color::Reset,
+            header: Color::Reset,
+            row_selected: Color::Reset,
+            column_selected: Color::Reset,
+            cell_selected: Color::Reset,
+            row_was_selected: Color::Reset,
+            column_was_selected: Color::Reset,
+            cell_editing: Color::Reset,
+            problem: Color::Reset,
+            hint: Color::Reset,
+            key: Color::Reset,
+        }
+    }
+
+    fn set_color(&mut self, name: &str, color: Color) {
+        match name {
+            "background" => self.background = color,
+            "font" => self.font = color,
+            "header" => self.header = color,
+            "row_selected" => self.row_selected = color,
+            "column_selected" => self.column_selected = color,
+            "cell_selected" => self.cell_selected = color,
+            "row_was_selected" => self.row_was_selected = color,
+            "column_was_selected" => self.column_was_selected = color,
+            "cell_editing" => self.cell_editing = color,
+            "problem" => self.problem = color,
+            "hint" => self.hint = color,
+            "key" => self.key = color,
+            _ => {}
+        }
+    }
+}
+
+/// Load the built-in themes and the ones of `theme.toml` in the configuration folder.<br>
+/// Returns the themes and the index of the active one.
+pub fn load_themes() -> (Vec<Theme>, usize) {
+    let mut themes = vec![
+        Theme::dark(),
+        Theme::light(),
+        Theme::high_contrast(),
+        Theme::ansi(),
+        Theme::no_color(),
+    ];
+    let mut active = if env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+        String::from("no-color")
+    } else {
+        String::from("dark")
+    };
+
+    if let Ok(mut path) = get_config_path() {
+        path.push(THEME_NAME);
+        if let Ok(content) = fs::read_to_string(&path) {
+            match parse_themes(&content, &mut themes) {
+                Ok(Some(name)) if !active.eq("no-color") => active = name,
+                Ok(_) => {}
+                Err(error) => Logs::error("theme.rs - load_themes()", error),
+            }
+        }
+    }
+
+    let index = themes.iter().position(|t| t.name == active).unwrap_or(0);
+    (themes, index)
+}
+
+/// Add the themes of the file, each one is based on a built-in theme :
+/// ```toml
+/// theme = "mine"
+///
+/// [themes.mine]
+/// base = "light"
+/// header = "#da5d48"
+/// ```
+/// Nothing is added when the file has an error.
+fn parse_themes(content: &str, themes: &mut Vec<Theme>) -> Result<Option<String>> {
+    let table = content.parse::<Table>()?;
+    // The themes are only added once the whole file is valid
+    let mut parsed: Vec<Theme> = Vec::new();
+
+    if let Some(custom) = table.get("themes").and_then(|t| t.as_table()) {
+        for (name, colors) in custom {
+            let colors = colors.as_table().ok_or(Error::Parse(format!(
+                "The theme '{}' must be a table",
+                name
+            )))?;
+            let base = colors
+                .get("base")
+                .and_then(|b| b.as_str())
+                .unwrap_or("dark");
+            let mut theme = parsed
+                .iter()
+                .chain(themes.iter())
+                .find(|t| t.name == base)
+                .cloned()
+                .ok_or(Error::Parse(format!("Unknown base theme '{}'", base)))?;
+            theme.name = String::clone(name);
+
+            for (key, value) in colors.iter().filter(|(k, _)| *k != "base") {
+                if !COLORS.contains(&key.as_str()) {
+                    return Err(Error::Parse(format!(
+                        "Unknown color '{}' in the theme '{}'",
+                        key, name
+                    )));
+                }
+                let color =
+                    value
+                        .as_str()
+                        .and_then(|v| Color::from_str(v).ok())
+                        .ok_or(Error::Parse(format!(
+                            "Invalid color for '{}' in the theme '{}'",
+                            key, name
+                        )))?;
+                theme.set_color(key, color);
+            }
+            parsed.retain(|t| t.name != *name);
+            parsed.push(theme);
+        }
+    }
+    themes.retain(|t| parsed.iter().all(|p| p.name != t.name));
+    themes.extend(parsed);
+
+    Ok(table
+        .get("theme")
+        .and_then(|t| t.as_str())
+        .map(String::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nothing_on_error() {
+        let mut themes = vec![Theme::dark(), Theme::light()];
+        let content = r##"
+            theme = "a-mine"
+
+            [themes.a-mine]
+            base = "light"
+            header = "#da5d48"
+
+            [themes.b-broken]
+            header = "not a color"
+        "##;
+        assert!(parse_themes(content, &mut themes).is_err());
+        assert_eq!(
+            themes.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["dark", "light"]
+        );
+    }
+
+    #[test]
+    fn parse_themes_based_on_others() {
+        let mut themes = vec![Theme::dark(), Theme::light()];
+        let content = r##"
+            theme = "b-mine"
+
+            [themes.a-base]
+            base = "light"
+            header = "#da5d48"
+
+            [themes.b-mine]
+            base = "a-base"
+            hint = "#000000"
+        "##;
+        let active = parse_themes(content, &mut themes).unwrap();
+        assert_eq!(active.as_deref(), Some("b-mine"));
+        assert_eq!(
+            themes.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["dark", "light", "a-base", "b-mine"]
+        );
+        assert_eq!(themes[3].header, themes[2].header);
+    }
+}
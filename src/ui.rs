@@ -1,28 +1,45 @@
-use crate::database::AVAILABLE_SHEME;
+use crate::database::{AVAILABLE_SHEME, Rule, get_secrets};
+use crate::keymap::{Action, Context, Keymap, get_description};
 use crate::objects::*;
+use crate::theme::Theme;
+use crate::utils::{format_date, unix_now};
 use ratatui::{
-    layout::Rect, prelude::Constraint, style::{Color, Modifier, Style, Stylize}, symbols::border, text::{Line, Text}, widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, Table, TableState}, Frame
+    Frame,
+    layout::{Flex, Layout, Margin, Rect},
+    prelude::Constraint,
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Cell, Clear, HighlightSpacing, Padding, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
+    },
 };
 use unicode_width::UnicodeWidthStr;
 
-const ROW_BG: Color = Color::Black;
-const ROW_SELECTED: Color = Color::Rgb(155, 175, 223);
-const ROW_FONT: Color = Color::Rgb(227, 151, 143);
-const COLUMN_SELECTED: Color = Color::Rgb(101, 175, 223);
-const CELL_SELECTED: Color = Color::Rgb(155, 175, 223);
-const HEADER: Color = Color::Rgb(218, 93, 72);
-
-const ROW_WAS_SELECTED: Color = Color::Rgb(117, 146, 206);
-const COLUMN_WAS_SELECTED: Color = Color::Rgb(117, 146, 206);
-const CELL_EDITING: Color = Color::Rgb(151, 192, 80);
-
 const ROW_HEIGHT: u16 = 3;
 const HEADER_HEIGHT: u16 = 3;
+/// Put before the name of the marked shortcuts
+const MARK: &str = "● ";
+/// Put after the values inherited from the catalog
+const INHERITED: &str = "  (catalog)";
+pub const PREVIEW_HEIGHT: u16 = 5;
+/// Width of the toasts showing the errors
+const TOAST_WIDTH: u16 = 50;
+pub const STATUS_BAR_HEIGHT: u16 = 1;
+/// Under this width the panes are stacked vertically
+pub const NARROW_WIDTH: u16 = 80;
 
 #[derive(Debug)]
 pub struct WidgetShortcuts {
     values: Vec<Shortcut>,
     state: State,
+    /// Render each row on a single line
+    compact: bool,
+    /// The names of the shortcuts marked to be saved in a workspace, in the order of marking
+    marked: Vec<String>,
+    /// The title of the block, with the name of the active profile
+    title: String,
 }
 
 #[derive(Debug)]
@@ -30,11 +47,31 @@ pub struct WidgetConfigurations {
     values: Vec<Configuration>,
     state: State,
     hidde: bool,
+    /// Render each row on a single line
+    compact: bool,
+    /// The validation rules of each value
+    rules: Vec<Rule>,
+    /// The validation error of each value
+    errors: Vec<Option<String>>,
 }
 
 impl WidgetShortcuts {
     pub fn from(values: Vec<Shortcut>, state: State) -> Self {
-        WidgetShortcuts { values, state }
+        WidgetShortcuts {
+            values,
+            state,
+            compact: false,
+            marked: vec![],
+            title: String::from(" Shortcuts "),
+        }
+    }
+
+    /// Show the name of the active profile in the title, there is none with `--db`.
+    pub fn set_profile(&mut self, profile: Option<&str>) {
+        self.title = match profile {
+            Some(profile) => format!(" Shortcuts ({}) ", profile),
+            None => String::from(" Shortcuts "),
+        }
     }
 
     pub fn get_values(&self) -> &Vec<Shortcut> {
@@ -60,6 +97,26 @@ impl WidgetShortcuts {
             self.values = vec![Shortcut::default()]
         }
     }
+
+    pub fn compact(&mut self) {
+        self.compact = !self.compact
+    }
+
+    pub fn toggle_mark(&mut self, name: &str) {
+        if let Some(index) = self.marked.iter().position(|n| n == name) {
+            self.marked.remove(index);
+        } else {
+            self.marked.push(String::from(name));
+        }
+    }
+
+    pub fn get_marked(&self) -> &Vec<String> {
+        &self.marked
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear()
+    }
 }
 
 impl WidgetConfigurations {
@@ -68,6 +125,9 @@ impl WidgetConfigurations {
             values,
             state,
             hidde: true,
+            compact: false,
+            rules: vec![],
+            errors: vec![],
         }
     }
 
@@ -98,6 +158,43 @@ impl WidgetConfigurations {
     pub fn hidde(&mut self) {
         self.hidde = !self.hidde
     }
+
+    pub fn compact(&mut self) {
+        self.compact = !self.compact
+    }
+
+    /// Check every value against the rules of its scheme.<br>
+    /// The unset properties are reported when the shortcut is opened instead.
+    pub fn validate(&mut self, rules: Vec<Rule>) {
+        self.errors = self
+            .values
+            .iter()
+            .zip(rules.iter())
+            .map(|(configuration, rule)| {
+                if configuration.is_unset() {
+                    None
+                } else {
+                    rule.validate(configuration.get_value())
+                        .err()
+                        .map(|error| error.to_string())
+                }
+            })
+            .collect();
+        self.rules = rules;
+    }
+
+    pub fn get_errors(&self) -> &Vec<Option<String>> {
+        &self.errors
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidde
+    }
+
+    /// Get the values of the properties that must be masked.
+    pub fn get_secrets(&self) -> Vec<&String> {
+        get_secrets(&self.values)
+    }
 }
 
 impl Common for WidgetShortcuts {
@@ -105,11 +202,17 @@ impl Common for WidgetShortcuts {
         [" Kind ", " Name "]
     }
     fn get_title(&self) -> &str {
-        " Shortcuts "
+        &self.title
     }
     fn get_common_state(&self) -> State {
         State::clone(&self.state)
     }
+    fn set_common_state(&mut self, state: State) {
+        self.state = state
+    }
+    fn is_compact(&self) -> bool {
+        self.compact
+    }
 
     fn constraint_len_calculator(&self) -> (u16, u16) {
         let name_len = self
@@ -118,6 +221,7 @@ impl Common for WidgetShortcuts {
             .map(|cnx| cnx.get_name().width())
             .max()
             .unwrap_or(0)
+            + MARK.width()
             + 1;
         let kind_len = self
             .values
@@ -130,16 +234,21 @@ impl Common for WidgetShortcuts {
         (kind_len.max(6) as u16, name_len.max(5) as u16)
     }
 
-    fn get_rows(&self) -> Vec<ratatui::widgets::Row<'_>> {
+    fn get_rows(&self, theme: &Theme) -> Vec<ratatui::widgets::Row<'_>> {
         self.values
             .iter()
             .map(|shortcut| {
-                let item = [shortcut.get_kind(), shortcut.get_name()];
+                let name = if self.marked.contains(shortcut.get_name()) {
+                    format!("{}{}", MARK, shortcut.get_name())
+                } else {
+                    String::clone(shortcut.get_name())
+                };
+                let item = [shortcut.get_kind(), &name];
                 item.into_iter()
-                    .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
+                    .map(|content| Cell::from(padded(content, self.compact)))
                     .collect::<Row>()
-                    .style(Style::new().fg(ROW_FONT).bg(ROW_BG))
-                    .height(ROW_HEIGHT)
+                    .style(Style::new().fg(theme.font).bg(theme.background))
+                    .height(heights(self.compact).1)
             })
             .collect()
     }
@@ -163,12 +272,29 @@ impl Common for WidgetConfigurations {
     fn get_common_state(&self) -> State {
         State::clone(&self.state)
     }
+    fn set_common_state(&mut self, state: State) {
+        self.state = state
+    }
+    fn is_compact(&self) -> bool {
+        self.compact
+    }
 
     fn constraint_len_calculator(&self) -> (u16, u16) {
         let value_len = self
             .get_values()
             .iter()
-            .map(|cnx| cnx.get_value().width())
+            .zip(self.errors.iter().chain(std::iter::repeat(&None)))
+            .map(|(cnx, error)| match error {
+                // The error is on the same line as the value in compact mode
+                Some(error) if self.compact => {
+                    single_line(cnx.get_value()).width() + error.width() + 4
+                }
+                Some(error) => single_line(cnx.get_value()).width().max(error.width() + 2),
+                None if cnx.is_inherited() => {
+                    single_line(cnx.get_value()).width() + INHERITED.width()
+                }
+                None => single_line(cnx.get_value()).width(),
+            })
             .max()
             .unwrap_or(4)
             + 1;
@@ -180,37 +306,62 @@ impl Common for WidgetConfigurations {
             .unwrap_or(4)
             + 1;
         #[allow(clippy::cast_possible_truncation)]
-        (kind_len.max(9) as u16, value_len.max(6) as u16)
-    }
-
-    fn get_rows(&self) -> Vec<Row<'_>> {
-        if self.hidde {
-            self.values
-                .iter()
-                .map(|configuration| {
-                    let mut item = [configuration.get_kind(), configuration.get_value()];
-                    let binding = "*".repeat(item[1].len());
-                    item[1] = &binding;
-                    item.into_iter()
-                        .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                        .collect::<Row>()
-                        .style(Style::new().fg(ROW_FONT).bg(ROW_BG))
-                        .height(ROW_HEIGHT)
-                })
-                .collect()
-        } else {
-            self.values
-                .iter()
-                .map(|configuration| {
-                    let item = [configuration.get_kind(), configuration.get_value()];
-                    item.into_iter()
-                        .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                        .collect::<Row>()
-                        .style(Style::new().fg(ROW_FONT).bg(ROW_BG))
-                        .height(ROW_HEIGHT)
-                })
-                .collect()
-        }
+        (kind_len.max(9) as u16, value_len.max(9) as u16)
+    }
+
+    fn get_rows(&self, theme: &Theme) -> Vec<Row<'_>> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(index, configuration)| {
+                let value = if configuration.is_unset() {
+                    let hint = match self.rules.get(index) {
+                        Some(rule) if rule.is_required() => "required",
+                        _ => "optional",
+                    };
+                    let hint = Line::from(hint).fg(theme.hint).italic();
+                    if self.compact {
+                        Cell::from(hint)
+                    } else {
+                        Cell::from(Text::from(vec![Line::from(""), hint]))
+                    }
+                } else if self.hidde {
+                    value_cell(
+                        "*".repeat(configuration.get_value().len()),
+                        None,
+                        self.compact,
+                        theme,
+                    )
+                } else if configuration.is_inherited() {
+                    let line = Line::from(vec![
+                        Span::from(single_line(configuration.get_value())),
+                        Span::from(INHERITED).fg(theme.hint).italic(),
+                    ]);
+                    if self.compact {
+                        Cell::from(line)
+                    } else {
+                        Cell::from(Text::from(vec![Line::from(""), line]))
+                    }
+                } else {
+                    value_cell(
+                        single_line(configuration.get_value()),
+                        self.get_error(index),
+                        self.compact,
+                        theme,
+                    )
+                };
+                Row::new(vec![
+                    Cell::from(padded(configuration.get_kind(), self.compact)),
+                    value,
+                ])
+                .style(Style::new().fg(theme.font).bg(theme.background))
+                .height(heights(self.compact).1)
+            })
+            .collect()
+    }
+
+    fn get_error(&self, index: usize) -> Option<&String> {
+        self.errors.get(index).and_then(|e| e.as_ref())
     }
 
     fn get_editing_value(&self, index: usize) -> [String; 2] {
@@ -229,20 +380,33 @@ pub trait Common {
 
     fn get_common_state(&self) -> State;
 
-    fn get_rows(&self) -> Vec<ratatui::widgets::Row<'_>>;
+    fn set_common_state(&mut self, state: State);
+
+    fn is_compact(&self) -> bool;
+
+    fn get_rows(&self, theme: &Theme) -> Vec<ratatui::widgets::Row<'_>>;
 
     fn get_editing_value(&self, index: usize) -> [String; 2];
 
+    /// Get the validation error of the row, if any.
+    fn get_error(&self, _index: usize) -> Option<&String> {
+        None
+    }
+
     fn constraint_len_calculator(&self) -> (u16, u16);
 
-    fn render(&mut self, frame: &mut Frame, area: Rect) {
+    fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let header = self
             .get_header()
             .into_iter()
             .map(Cell::from)
             .collect::<Row>()
-            .style(Style::default().add_modifier(Modifier::BOLD).fg(HEADER))
-            .height(HEADER_HEIGHT);
+            .style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme.header),
+            )
+            .height(heights(self.is_compact()).0);
 
         let selected_row_style: Style;
         let selected_col_style: Style;
@@ -250,62 +414,69 @@ pub trait Common {
 
         let mut rows: Vec<ratatui::widgets::Row<'_>>;
         let len_constraints = self.constraint_len_calculator();
+        // The column of the cursor in the edited value
+        let mut cursor: Option<u16> = None;
 
         match self.get_common_state() {
             State::Selected(_) => {
                 selected_row_style = Style::default()
                     .add_modifier(Modifier::REVERSED)
-                    .fg(ROW_SELECTED);
-                selected_col_style = Style::default().fg(COLUMN_SELECTED);
+                    .fg(theme.row_selected);
+                selected_col_style = Style::default().fg(theme.column_selected);
                 selected_cell_style = Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(CELL_SELECTED);
-                rows = self.get_rows();
+                    .fg(theme.cell_selected);
+                rows = self.get_rows(theme);
             }
             State::WasSelected(_) => {
                 selected_row_style = Style::default()
                     .add_modifier(Modifier::REVERSED)
-                    .fg(ROW_WAS_SELECTED);
-                selected_col_style = Style::default().fg(COLUMN_WAS_SELECTED);
+                    .fg(theme.row_was_selected);
+                selected_col_style = Style::default().fg(theme.column_was_selected);
                 selected_cell_style = Style::default();
-                rows = self.get_rows();
+                rows = self.get_rows(theme);
             }
             State::Editing(ts, input) => {
                 selected_row_style = Style::default()
                     .add_modifier(Modifier::REVERSED)
-                    .fg(ROW_WAS_SELECTED);
-                selected_col_style = Style::default().fg(COLUMN_SELECTED);
+                    .fg(theme.row_was_selected);
+                selected_col_style = Style::default().fg(theme.column_selected);
                 selected_cell_style = Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(CELL_EDITING);
-                rows = self.get_rows();
+                    .fg(theme.cell_editing);
+                rows = self.get_rows(theme);
 
                 if let Some(index) = ts.selected() {
                     // Updating the input value in the target row
-                    let mut editing_value = self.get_editing_value(index);
-                    editing_value[1] = input.value().into();
-                    rows[index] = editing_value
-                        .into_iter()
-                        .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                        .collect::<Row>()
-                        .style(Style::new().bg(Color::Black))
-                        .height(ROW_HEIGHT);
-
-                    let width = area.width.max(2) - 3;
-                    let scroll = input.visual_scroll(width as usize);
-                    let cursor_pos = (input.visual_cursor().max(scroll) - scroll + 1) as u16;
+                    let [kind, _] = self.get_editing_value(index);
+                    rows[index] = Row::new(vec![
+                        Cell::from(padded(&kind, self.is_compact())),
+                        value_cell(
+                            input.value().into(),
+                            self.get_error(index),
+                            self.is_compact(),
+                            theme,
+                        ),
+                    ])
+                    .style(Style::new().bg(theme.background))
+                    .height(heights(self.is_compact()).1);
 
-                    let (current_x, current_y) =
-                        calculate_cursor_position(area, cursor_pos, len_constraints.0, index);
-                    frame.set_cursor_position((current_x, current_y));
+                    let width = area.width.saturating_sub(3);
+                    let scroll = input.visual_scroll(width as usize);
+                    cursor = Some((input.visual_cursor().max(scroll) - scroll + 1) as u16);
                 }
             }
         }
 
+        let len = rows.len();
         let block = Block::bordered()
             .border_set(border::ROUNDED)
             .title_top(Line::from(self.get_title()).centered())
-            .title_style(Style::default().add_modifier(Modifier::BOLD).fg(HEADER));
+            .title_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme.header),
+            );
         let t = Table::new(
             rows,
             [
@@ -317,110 +488,887 @@ pub trait Common {
         .row_highlight_style(selected_row_style)
         .column_highlight_style(selected_col_style)
         .cell_highlight_style(selected_cell_style)
-        .highlight_symbol(Text::from(vec!["".into(), " █ ".into()]))
-        .bg(Color::Black)
-        .fg(ROW_FONT)
+        .highlight_symbol(if self.is_compact() {
+            Text::from(" █ ")
+        } else {
+            Text::from(vec!["".into(), " █ ".into()])
+        })
+        .bg(theme.background)
+        .fg(theme.font)
         .highlight_spacing(HighlightSpacing::Always)
         .block(block);
-    
-        match self.get_common_state() {
-            State::Selected(mut ts) | State::WasSelected(mut ts) | State::Editing(mut ts, _) => {
-                frame.render_stateful_widget(t, area, &mut ts)
+
+        // The offset computed by the table is kept, the next frames scroll from it
+        let ts = match self.get_common_state() {
+            State::Selected(mut ts) => {
+                frame.render_stateful_widget(t, area, &mut ts);
+                self.set_common_state(State::Selected(TableState::clone(&ts)));
+                ts
+            }
+            State::WasSelected(mut ts) => {
+                frame.render_stateful_widget(t, area, &mut ts);
+                self.set_common_state(State::WasSelected(TableState::clone(&ts)));
+                ts
             }
+            State::Editing(mut ts, input) => {
+                frame.render_stateful_widget(t, area, &mut ts);
+                self.set_common_state(State::Editing(TableState::clone(&ts), input));
+                ts
+            }
+        };
+
+        if let (Some(cursor), Some(index)) = (cursor, ts.selected()) {
+            frame.set_cursor_position(calculate_cursor_position(
+                area,
+                cursor,
+                len_constraints.0,
+                index.saturating_sub(ts.offset()),
+                self.is_compact(),
+            ));
         }
+        render_scrollbar(frame, area, len, ts.offset(), self.is_compact());
+    }
+}
+
+/// Show a multi-line value on its first line, with the number of other lines.
+fn single_line(value: &str) -> String {
+    match value.split_once('\n') {
+        Some((first, _)) => format!("{} ⏎ (+{} line(s))", first, value.split('\n').count() - 1),
+        None => value.to_string(),
+    }
+}
+
+/// Get the height of the header and of each row of a table.
+fn heights(compact: bool) -> (u16, u16) {
+    if compact {
+        (1, 1)
+    } else {
+        (HEADER_HEIGHT, ROW_HEIGHT)
+    }
+}
+
+/// Put the content on the middle line of the row, unless the rows are compact.
+fn padded<'a>(content: &str, compact: bool) -> Text<'a> {
+    if compact {
+        Text::from(content.to_string())
+    } else {
+        Text::from(format!("\n{content}\n"))
+    }
+}
+
+/// Get the number of rows that fit in a table rendered in `area`.
+pub fn visible_rows(area: Rect, compact: bool) -> usize {
+    let (header, row) = heights(compact);
+    usize::from(area.height.saturating_sub(2 + header) / row).max(1)
+}
+
+/// Render a scrollbar on the right border of a table when its rows overflow.
+fn render_scrollbar(frame: &mut Frame, area: Rect, len: usize, offset: usize, compact: bool) {
+    let visible = visible_rows(area, compact);
+    if len <= visible {
+        return;
+    }
+    let mut state = ScrollbarState::new(len - visible).position(offset);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
+
+/// Get the index of the row under the line `y` of a table rendered in `area`.
+pub fn row_at(area: Rect, compact: bool, offset: usize, len: usize, y: u16) -> Option<usize> {
+    let (header, row) = heights(compact);
+    hit_row(area, header, row, offset, len, y)
+}
+
+/// Get the index of the kind under the line `y` of the pop-up rendered in `area`.
+pub fn pop_up_row_at(area: Rect, offset: usize, y: u16) -> Option<usize> {
+    hit_row(area, 0, HEADER_HEIGHT, offset, AVAILABLE_SHEME.len(), y)
+}
+
+fn hit_row(
+    area: Rect,
+    header_height: u16,
+    row_height: u16,
+    offset: usize,
+    len: usize,
+    y: u16,
+) -> Option<usize> {
+    // The rows are inside the borders, under the header
+    let top = area.y + 1 + header_height;
+    if y < top || y + 1 >= area.bottom() {
+        return None;
+    }
+    let index = offset + usize::from((y - top) / row_height);
+    (index < len).then_some(index)
+}
+
+/// Build the cell of a value, with its validation error on the line under it.
+fn value_cell<'a>(value: String, error: Option<&String>, compact: bool, theme: &Theme) -> Cell<'a> {
+    match error {
+        Some(error) if compact => Cell::from(Line::from(vec![
+            Span::from(value),
+            Span::from(format!("  ⚠ {}", error)).fg(theme.problem),
+        ])),
+        Some(error) => Cell::from(Text::from(vec![
+            Line::from(""),
+            Line::from(value),
+            Line::from(format!("⚠ {}", error)).fg(theme.problem),
+        ])),
+        None => Cell::from(padded(&value, compact)),
     }
 }
 
+/// Get the position of the cursor in the value of the row, `row` is counted from the first visible one.
 fn calculate_cursor_position(
     area: Rect,
     cursor: u16,
     left_constraint: u16,
-    index: usize,
+    row: usize,
+    compact: bool,
 ) -> (u16, u16) {
     let x = area.x + cursor + left_constraint + 5u16;
-    let index = if index > 11 { 11_u16 } else { index as u16 };
-    let y = area.y + 2u16 * (index + 1) + (index) + 2u16;
+    // The value is on the middle line of the row, under the borders and the header
+    let (header, height) = heights(compact);
+    #[allow(clippy::cast_possible_truncation)]
+    let y = area.y + 1 + header + row as u16 * height + height / 2;
     (x, y)
 }
 
-pub fn render_pop_up(frame: &mut Frame, index: usize, area: Rect) {
+/// Render the kinds of Shortcut and return the offset of the list.
+pub fn render_pop_up(frame: &mut Frame, index: usize, area: Rect, theme: &Theme) -> usize {
     let mut rows: Vec<Row<'_>> = AVAILABLE_SHEME
         .iter()
         .map(|kind| {
             let item = (*kind).to_string();
             Row::default()
                 .cells(vec![Line::from(item).centered()])
-                .style(Style::new().fg(ROW_FONT).bg(ROW_BG))
+                .style(Style::new().fg(theme.font).bg(theme.background))
                 .height(HEADER_HEIGHT)
         })
         .collect();
 
     rows[index] = Row::clone(&rows[index])
-        .style(Style::new().bg(Color::Black).fg(CELL_SELECTED))
+        .style(
+            Style::new()
+                .bg(theme.background)
+                .fg(theme.cell_selected)
+                .add_modifier(Modifier::BOLD),
+        )
         .height(HEADER_HEIGHT);
 
     let block = Block::bordered()
         .border_set(border::ROUNDED)
         .title(Line::from(" Select a kind of Shortcut ").centered())
-        .title_style(Style::default().add_modifier(Modifier::BOLD).fg(HEADER))
-        .style(Style::default().fg(ROW_FONT).bg(Color::Black));
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .style(Style::default().fg(theme.font).bg(theme.background));
 
     let t = Table::new(rows, [Constraint::Percentage(100), Constraint::Length(0)])
-        .bg(Color::Black)
-        .fg(ROW_FONT)
+        .bg(theme.background)
+        .fg(theme.font)
         .block(block);
 
     let mut ts = TableState::default();
     ts.select(Some(index));
-    frame.render_stateful_widget(t, area, &mut ts)
+    frame.render_stateful_widget(t, area, &mut ts);
+    ts.offset()
 }
 
-pub fn render_help(frame: &mut Frame, area: Rect) {
-    let title = Line::from(" Help command ".bold());
+/// Render the command that will be written for the selected shortcut.
+pub fn render_preview(
+    frame: &mut Frame,
+    area: Rect,
+    command: &str,
+    problem: Option<&str>,
+    theme: &Theme,
+) {
+    let mut block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title_top(Line::from(" Preview ").centered())
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .bg(theme.background)
+        .fg(theme.font);
+    let mut text = Text::from(command.to_string()).style(theme.cell_selected);
+
+    if let Some(problem) = problem {
+        block = block
+            .border_style(Style::default().fg(theme.problem))
+            .title_bottom(
+                Line::from(format!(" ⚠ {} ", problem))
+                    .centered()
+                    .fg(theme.problem),
+            );
+        text = text.style(theme.problem);
+    }
+
+    let p = Paragraph::new(text).wrap(Wrap { trim: false }).block(block);
+    frame.render_widget(p, area);
+}
+
+/// Render a modal asking to confirm a destructive action.<br>
+/// The message should name what will be lost.
+pub fn render_confirmation(
+    frame: &mut Frame,
+    title: &str,
+    message: &str,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let footer = key_hints(
+        keymap,
+        Context::Confirmation,
+        &[Action::Confirm, Action::Cancel],
+        theme,
+    );
+    render_modal(frame, title, message, footer, theme);
+}
+
+/// Render a modal showing a message until the user closes it.
+pub fn render_alert(frame: &mut Frame, title: &str, message: &str, keymap: &Keymap, theme: &Theme) {
+    let footer = key_hints(keymap, Context::Alert, &[Action::Close], theme);
+    render_modal(frame, title, message, footer, theme);
+}
+
+/// Render the errors in toasts stacked from the bottom right corner, the last one at the bottom.
+pub fn render_toasts(frame: &mut Frame, area: Rect, messages: &[&str], theme: &Theme) {
+    let width = TOAST_WIDTH.min(area.width);
+    let inner_width = width.saturating_sub(4).max(1) as usize;
+    let mut bottom = area.bottom();
+    for message in messages.iter().rev() {
+        let lines = message
+            .lines()
+            .map(|line| line.width().div_ceil(inner_width).max(1))
+            .sum::<usize>() as u16;
+        let height = (lines + 2).min(bottom.saturating_sub(area.y));
+        if height < 3 {
+            break;
+        }
+        bottom -= height;
+        let toast = Rect::new(area.right() - width, bottom, width, height);
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .title(Line::from(" Error ").left_aligned())
+            .title_style(Style::default().add_modifier(Modifier::BOLD))
+            .border_style(Style::default().fg(theme.problem))
+            .bg(theme.background)
+            .fg(theme.font);
+        let p = Paragraph::new(message.to_string())
+            .wrap(Wrap { trim: false })
+            .block(block.padding(Padding::horizontal(1)));
+        frame.render_widget(Clear, toast);
+        frame.render_widget(p, toast);
+    }
+}
+
+/// Render the bar at the bottom of the screen : the mode, the result of the last action,
+/// then the number of shortcuts and the database on the right.<br>
+/// `status` is the message and whether it's an error.
+pub fn render_status_bar(
+    frame: &mut Frame,
+    area: Rect,
+    mode: &str,
+    status: Option<(&str, bool)>,
+    count: usize,
+    database: &str,
+    theme: &Theme,
+) {
+    let mode = format!(" {} ", mode.replace('_', " ").to_uppercase());
+    let right = format!(
+        " {} shortcut{} │ {} ",
+        count,
+        if count == 1 { "" } else { "s" },
+        database
+    );
+    let mut spans = vec![Span::styled(
+        mode,
+        Style::default()
+            .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            .fg(theme.header),
+    )];
+    if let Some((message, error)) = status {
+        let color = if error { theme.problem } else { theme.hint };
+        let message = message.lines().next().unwrap_or_default();
+        spans.push(Span::styled(
+            format!(" {}", message),
+            Style::default().fg(color),
+        ));
+    }
+
+    // The message is cut when it's too long, the count and the database are kept
+    let layout = Layout::horizontal([Constraint::Min(0), Constraint::Length(right.width() as u16)])
+        .split(area);
+    let style = Style::default().bg(theme.background).fg(theme.font);
+    frame.render_widget(Paragraph::new(Line::from(spans)).style(style), layout[0]);
+    frame.render_widget(
+        Paragraph::new(Line::from(right).right_aligned()).style(style),
+        layout[1],
+    );
+}
+
+/// Render the menu choosing where the shortcut `name` is opened.
+pub fn render_open_menu(
+    frame: &mut Frame,
+    name: &str,
+    modes: &[&str],
+    index: usize,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let area = centered_rect(frame.area(), 50, modes.len() as u16 + 4);
+    let mut lines = modes
+        .iter()
+        .enumerate()
+        .map(|(i, mode)| {
+            let line = Line::from(mode.to_string()).centered();
+            if i == index {
+                line.style(
+                    Style::new()
+                        .fg(theme.cell_selected)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<Line>>();
+    lines.push(Line::from(""));
+    lines.push(
+        key_hints(
+            keymap,
+            Context::PopUp,
+            &[Action::Select, Action::Cancel],
+            theme,
+        )
+        .centered(),
+    );
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(format!(" Open '{}' in ", name)).centered())
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .bg(theme.background)
+        .fg(theme.font);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+fn render_modal(frame: &mut Frame, title: &str, message: &str, footer: Line, theme: &Theme) {
+    let area = centered_rect(frame.area(), 60, 9);
     let lines = vec![
-        Line::from(vec![
-            " Select : ".into(),
-            "[Up]".light_cyan(),
-            " | ".into(),
-            "[Down]".light_cyan(),
-            " | ".into(),
-            "[Left]".light_cyan(),
-            " | ".into(),
-            "[Right]".light_cyan(),
-        ]),
-        Line::from(vec![" Add new shortcut : ".into(), "[a] ".light_cyan()]),
-        Line::from(vec![
-            " Remove shortcut/config : ".into(),
-            "[r] ".light_cyan(),
-        ]),
-        Line::from(vec![
-            " Open the selected shortcut : ".into(),
-            "[o] ".light_cyan(),
-        ]),
-        Line::from(vec![
-            " Edit shortcut/config : ".into(),
-            "[e] ".light_cyan(),
-        ]),
-        Line::from(vec![" Save changes : ".into(), "[Enter] ".light_cyan()]),
-        Line::from(vec![
-            " Hidde/Show configs : ".into(),
-            "[h] ".light_cyan(),
-        ]),
-        Line::from(vec![
-            " Quit : ".into(),
-            "[q]".light_cyan(),
-            " | ".into(),
-            "[Esc] ".light_cyan(),
-        ]),
+        Line::from(""),
+        Line::from(message.to_string()).centered(),
+        Line::from(""),
+        footer.centered(),
     ];
 
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(format!(" {} ", title)).centered())
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .bg(theme.background)
+        .fg(theme.font);
+
+    let p = Paragraph::new(Text::from(lines))
+        .wrap(Wrap { trim: false })
+        .block(block);
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+}
+
+pub fn render_trash(
+    frame: &mut Frame,
+    entries: &[TrashEntry],
+    index: usize,
+    area: Rect,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let now = unix_now();
+    let rows: Vec<Row<'_>> = entries
+        .iter()
+        .map(|entry| {
+            let days = (now - entry.get_deleted_at()).max(0) / 86400;
+            let item = [
+                String::clone(entry.get_kind()),
+                String::clone(entry.get_name()),
+                format!("{} day(s) ago", days),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
+                .collect::<Row>()
+                .style(Style::new().fg(theme.font).bg(theme.background))
+                .height(ROW_HEIGHT)
+        })
+        .collect();
+
+    let header = [" Kind ", " Name ", " Deleted "]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .height(HEADER_HEIGHT);
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(" Trash ").centered())
+        .title_bottom(
+            key_hints(
+                keymap,
+                Context::Trash,
+                &[
+                    Action::Restore,
+                    Action::Purge,
+                    Action::PurgeAll,
+                    Action::Close,
+                ],
+                theme,
+            )
+            .centered(),
+        )
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .style(Style::default().fg(theme.font).bg(theme.background));
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(
+        Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(theme.row_selected),
+    )
+    .highlight_symbol(Text::from(vec!["".into(), " █ ".into()]))
+    .highlight_spacing(HighlightSpacing::Always)
+    .bg(theme.background)
+    .fg(theme.font)
+    .block(block);
+
+    let mut ts = TableState::default();
+    if !entries.is_empty() {
+        ts.select(Some(index));
+    }
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(t, area, &mut ts)
+}
+
+pub fn render_workspaces(
+    frame: &mut Frame,
+    workspaces: &[Workspace],
+    index: usize,
+    area: Rect,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let rows: Vec<Row<'_>> = workspaces
+        .iter()
+        .map(|workspace| {
+            let item = [
+                String::clone(workspace.get_name()),
+                String::clone(workspace.get_layout()),
+                workspace
+                    .get_panes()
+                    .iter()
+                    .map(|pane| pane.get_shortcut().as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
+                .collect::<Row>()
+                .style(Style::new().fg(theme.font).bg(theme.background))
+                .height(ROW_HEIGHT)
+        })
+        .collect();
+
+    let header = [" Name ", " Layout ", " Shortcuts "]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .height(HEADER_HEIGHT);
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(" Workspaces ").centered())
+        .title_bottom(
+            labeled_hints(
+                keymap,
+                Context::Workspaces,
+                &[
+                    (Action::Open, "Open"),
+                    (Action::Add, "Save marked"),
+                    (Action::Edit, "Edit"),
+                    (Action::Remove, "Delete"),
+                    (Action::Close, "Close"),
+                ],
+                theme,
+            )
+            .centered(),
+        )
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .style(Style::default().fg(theme.font).bg(theme.background));
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Percentage(20),
+            Constraint::Percentage(55),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(
+        Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(theme.row_selected),
+    )
+    .highlight_symbol(Text::from(vec!["".into(), " █ ".into()]))
+    .highlight_spacing(HighlightSpacing::Always)
+    .bg(theme.background)
+    .fg(theme.font)
+    .block(block);
+
+    let mut ts = TableState::default();
+    if !workspaces.is_empty() {
+        ts.select(Some(index));
+    }
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(t, area, &mut ts)
+}
+
+pub fn render_history(
+    frame: &mut Frame,
+    entries: &[HistoryEntry],
+    index: usize,
+    area: Rect,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let rows: Vec<Row<'_>> = entries
+        .iter()
+        .map(|entry| {
+            let item = [
+                format_date(entry.get_opened_at()),
+                String::clone(entry.get_name()),
+                String::clone(entry.get_mode()),
+                entry
+                    .get_exit_code()
+                    .map_or(String::from("-"), |code| code.to_string()),
+                entry.get_command().replace('\n', " "),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
+                .collect::<Row>()
+                .style(Style::new().fg(theme.font).bg(theme.background))
+                .height(ROW_HEIGHT)
+        })
+        .collect();
+
+    let header = [" Date ", " Name ", " Mode ", " Exit ", " Command "]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .height(HEADER_HEIGHT);
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(" History ").centered())
+        .title_bottom(key_hints(keymap, Context::History, &[Action::Close], theme).centered())
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .style(Style::default().fg(theme.font).bg(theme.background));
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Length(17),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(
+        Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(theme.row_selected),
+    )
+    .highlight_symbol(Text::from(vec!["".into(), " █ ".into()]))
+    .highlight_spacing(HighlightSpacing::Always)
+    .bg(theme.background)
+    .fg(theme.font)
+    .block(block);
+
+    let mut ts = TableState::default();
+    if !entries.is_empty() {
+        ts.select(Some(index));
+    }
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(t, area, &mut ts)
+}
+
+/// Render the profiles, the active one is marked.
+pub fn render_profiles(
+    frame: &mut Frame,
+    profiles: &[String],
+    active: Option<&str>,
+    index: usize,
+    area: Rect,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let rows: Vec<Row<'_>> = profiles
+        .iter()
+        .map(|profile| {
+            let name = if active == Some(profile.as_str()) {
+                format!("{}{}", MARK, profile)
+            } else {
+                format!("  {}", profile)
+            };
+            Row::new([Cell::from(Text::from(format!("\n{name}\n")))])
+                .style(Style::new().fg(theme.font).bg(theme.background))
+                .height(ROW_HEIGHT)
+        })
+        .collect();
+
+    let header = Row::new([Cell::from(" Name ")])
+        .style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .height(HEADER_HEIGHT);
+
+    let block = Block::bordered()
+        .border_set(border::ROUNDED)
+        .title(Line::from(" Profiles ").centered())
+        .title_bottom(
+            labeled_hints(
+                keymap,
+                Context::Profiles,
+                &[
+                    (Action::Select, "Switch"),
+                    (Action::Copy, "Copy marked"),
+                    (Action::Move, "Move marked"),
+                    (Action::Close, "Close"),
+                ],
+                theme,
+            )
+            .centered(),
+        )
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .style(Style::default().fg(theme.font).bg(theme.background));
+
+    let t = Table::new(rows, [Constraint::Fill(1)])
+        .header(header)
+        .row_highlight_style(
+            Style::default()
+                .add_modifier(Modifier::REVERSED)
+                .fg(theme.row_selected),
+        )
+        .highlight_symbol(Text::from(vec!["".into(), " █ ".into()]))
+        .highlight_spacing(HighlightSpacing::Always)
+        .bg(theme.background)
+        .fg(theme.font)
+        .block(block);
+
+    let mut ts = TableState::default();
+    if !profiles.is_empty() {
+        ts.select(Some(index));
+    }
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(t, area, &mut ts)
+}
+
+/// Compute a rectangle of the given size centered in `area`.
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Format the bindings of the actions like `[a] | [Enter]`.
+fn key_spans<'a>(
+    keymap: &Keymap,
+    context: Context,
+    actions: &[Action],
+    theme: &Theme,
+) -> Vec<Span<'a>> {
+    let mut keys: Vec<String> = Vec::new();
+    for key in actions.iter().flat_map(|a| keymap.get_keys(context, *a)) {
+        // [a] is enough to understand that [A] works too
+        if !keys.iter().any(|k| k.eq_ignore_ascii_case(&key)) {
+            keys.push(key);
+        }
+    }
+
+    let mut spans = Vec::new();
+    for (index, key) in keys.into_iter().enumerate() {
+        if index > 0 {
+            spans.push(" | ".into());
+        }
+        spans.push(format!("[{}]", key).fg(theme.key));
+    }
+    spans
+}
+
+/// Format the description and the bindings of each action on a single line.
+pub fn key_hints<'a>(
+    keymap: &Keymap,
+    context: Context,
+    actions: &[Action],
+    theme: &Theme,
+) -> Line<'a> {
+    let actions = actions
+        .iter()
+        .map(|action| (*action, get_description(*action)))
+        .collect::<Vec<(Action, &str)>>();
+    labeled_hints(keymap, context, &actions, theme)
+}
+
+/// Same as `key_hints` with a label for each action, when its description doesn't fit.
+pub fn labeled_hints<'a>(
+    keymap: &Keymap,
+    context: Context,
+    actions: &[(Action, &str)],
+    theme: &Theme,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+    for (action, label) in actions {
+        spans.push(format!(" {} : ", label).into());
+        spans.extend(key_spans(keymap, context, &[*action], theme));
+        spans.push(" ".into());
+    }
+    Line::from(spans)
+}
+
+/// Lines of the help, generated from the active keymap.
+const HELP: [(Context, &[Action]); 18] = [
+    (
+        Context::Normal,
+        &[Action::Up, Action::Down, Action::Left, Action::Right],
+    ),
+    (
+        Context::Normal,
+        &[
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+        ],
+    ),
+    (Context::Normal, &[Action::Add]),
+    (Context::Normal, &[Action::Remove]),
+    (Context::Normal, &[Action::Open]),
+    (Context::Normal, &[Action::Edit]),
+    (Context::Editing, &[Action::Save]),
+    (Context::Normal, &[Action::External]),
+    (Context::Normal, &[Action::Mark]),
+    (Context::Normal, &[Action::Workspaces]),
+    (Context::Normal, &[Action::Trash]),
+    (Context::Normal, &[Action::History]),
+    (Context::Normal, &[Action::Profiles]),
+    (Context::Normal, &[Action::Hide]),
+    (Context::Normal, &[Action::Theme]),
+    (Context::Normal, &[Action::Compact]),
+    (Context::Normal, &[Action::Help]),
+    (Context::Normal, &[Action::Quit]),
+];
+
+/// Get the height needed to show the whole help.
+pub fn help_height() -> u16 {
+    HELP.len() as u16 + 2
+}
+
+pub fn render_help(frame: &mut Frame, area: Rect, keymap: &Keymap, theme: &Theme) {
+    let title = Line::from(" Help command ".bold());
+    let lines = HELP
+        .iter()
+        .map(|(context, actions)| {
+            let label = match actions {
+                [action] => get_description(*action),
+                [Action::PageUp, ..] => "Scroll",
+                _ => "Select",
+            };
+            let mut spans = vec![format!(" {} : ", label).into()];
+            spans.extend(key_spans(keymap, *context, actions, theme));
+            Line::from(spans)
+        })
+        .collect::<Vec<Line>>();
+
     let block = Block::bordered()
         .title(title.centered())
-        .title_style(Style::default().add_modifier(Modifier::BOLD).fg(HEADER))
-        .bg(Color::Black)
-        .fg(ROW_FONT)
+        .title_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(theme.header),
+        )
+        .bg(theme.background)
+        .fg(theme.font)
         .border_set(border::ROUNDED);
 
-    let p = Paragraph::new(Text::from(lines).style(CELL_SELECTED)).block(block);
+    let p = Paragraph::new(Text::from(lines).style(theme.cell_selected)).block(block);
     frame.render_widget(p, area);
 }
@@ -1,27 +1,363 @@
 use std::env;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
-
-pub struct Logs;
-
-impl Logs {
-    pub fn write(content: String) {
-        let mut path = get_folder_path().unwrap();
-        path.push("log.txt");
-        if let Ok(mut file) = OpenOptions::new().append(true).create(true).open(path) {
-            let _ = file.write(content.as_bytes());
-        }
-    }
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, Result};
+use crate::shell::ShellCommand;
+
+/// Name of the folder of the app, in the data and in the configuration folders.
+const APP_FOLDER: &str = "myshortcuts";
+
+/// Get the folder of the database and the logs : `$XDG_DATA_HOME/myshortcuts`,
+/// `~/.local/share/myshortcuts` by default (`%APPDATA%\myshortcuts` on Windows).
+pub fn get_data_path() -> Result<PathBuf> {
+    get_base_folder("XDG_DATA_HOME", ".local/share")
+}
+
+/// Get the folder of `config.toml`, `keymap.toml` and `theme.toml` : `$XDG_CONFIG_HOME/myshortcuts`,
+/// `~/.config/myshortcuts` by default (`%APPDATA%\myshortcuts` on Windows).
+pub fn get_config_path() -> Result<PathBuf> {
+    get_base_folder("XDG_CONFIG_HOME", ".config")
 }
 
-pub fn get_folder_path() -> Result<PathBuf, String> {
-    let mut exe_path = env::current_exe().map_err(|e| format!("{}", e))?;
+/// Get the folder used by the previous versions, next to the executable.
+pub fn get_legacy_path() -> Result<PathBuf> {
+    let mut exe_path = env::current_exe()
+        .map_err(|e| Error::io(String::from("Failed to locate the executable"), e))?;
     exe_path.pop();
     exe_path.push("myshortcuts_resources");
     Ok(exe_path)
 }
 
+fn get_base_folder(variable: &str, default: &str) -> Result<PathBuf> {
+    // The specification ignores the relative paths
+    let base = env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            if cfg!(windows) {
+                env::var_os("APPDATA").map(PathBuf::from)
+            } else {
+                env::var_os("HOME")
+                    .filter(|home| !home.is_empty())
+                    .map(|home| PathBuf::from(home).join(default))
+            }
+        })
+        .ok_or(Error::io(
+            format!("Failed to locate the home folder, set ${}", variable),
+            io::Error::from(io::ErrorKind::NotFound),
+        ))?;
+    Ok(base.join(APP_FOLDER))
+}
+
+/// Create the folder and its parents, the new ones are only readable by the user.
+pub fn create_private_folder(path: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        // The database holds passwords
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(path)
+        .map_err(|e| Error::io(format!("Failed to create '{}'", path.display()), e))
+}
+
+/// Parse the configuration stored in the database, formatted as `value0;value1;...;`<br>
+/// The `\`, `;` and new lines of the values are escaped, and `UNSET` marks the<br>
+/// properties that were never filled, returned as `None`.
+pub fn decode_config(config: &str) -> Vec<Option<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut unset = false;
+    let mut chars = config.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                fields.push(if unset && field.is_empty() {
+                    None
+                } else {
+                    Some(field)
+                });
+                field = String::new();
+                unset = false;
+            }
+            '\\' => match chars.next() {
+                Some('n') => field.push('\n'),
+                Some('u') => unset = true,
+                Some(escaped) => field.push(escaped),
+                None => field.push('\\'),
+            },
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || unset {
+        fields.push(if unset && field.is_empty() {
+            None
+        } else {
+            Some(field)
+        });
+    }
+    fields
+}
+
+/// Replace the leading `~` of a path by the home folder, as the shell would do.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{}{}", home, rest))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Replace the home folder at the start of a path by `~`, to display it shorter.
+pub fn shorten_home(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    match env::var("HOME") {
+        Ok(home) if !home.is_empty() && path.starts_with(&home) => {
+            let rest = &path[home.len()..];
+            if rest.is_empty() || rest.starts_with('/') {
+                format!("~{}", rest)
+            } else {
+                path.to_string()
+            }
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Check if the name can be written in the database and the tables, without `;` or control characters.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.chars().any(|c| c == ';' || c.is_control())
+}
+
+/// Check if the value is an IP address or a hostname as described in the RFC 1123.
+pub fn is_valid_host(value: &str) -> bool {
+    if value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .is_ok()
+    {
+        return true;
+    }
+    value.len() <= 253
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Current Unix timestamp in seconds.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Parse a date formatted as `YYYY-MM-DD` into the Unix timestamp of its midnight (UTC).
+pub fn parse_date(value: &str) -> Result<i64> {
+    let error = || Error::Parse(format!("Invalid date '{}', expected YYYY-MM-DD", value));
+    let parts = value
+        .split('-')
+        .map(|part| part.parse::<i64>().map_err(|_| error()))
+        .collect::<Result<Vec<i64>>>()?;
+    let [year, month, day] = parts[..] else {
+        return Err(error());
+    };
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) {
+        return Err(error());
+    }
+    // Days since 1970-01-01 of the proleptic Gregorian calendar
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Ok((era * 146097 + day_of_era - 719468) * 86400)
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` (UTC).
+pub fn format_date(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+/// Get the name of the user running the app.
+pub fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("LOGNAME"))
+        .unwrap_or(String::from("unknown"))
+}
+
+/// Escape a value stored in the database so `decode_config` can split it.
+pub fn encode_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+/// Run `args` (the program and its arguments) on the terminal of the app and wait for it.<br>
+/// Get its exit code, `128 + n` when it's killed by the signal `n`.
+pub fn execute(args: &[String]) -> Result<i32> {
+    let (program, args) = args
+        .split_first()
+        .ok_or(Error::command(String::from("There is no command to run")))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .spawn()
+        .map_err(|e| Error::io(format!("Failed to run '{}'", program), e))?;
+    // Ctrl-C must stop the command, not the app waiting for it
+    ignore_interrupts(true);
+    let status = child.wait();
+    ignore_interrupts(false);
+    let status = status?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return Ok(128 + signal);
+        }
+    }
+    Ok(status.code().unwrap_or(1))
+}
+
+fn ignore_interrupts(ignore: bool) {
+    #[cfg(unix)]
+    {
+        let handler = if ignore { libc::SIG_IGN } else { libc::SIG_DFL };
+        // SAFETY: only the default and the ignore dispositions are set, no handler runs
+        unsafe {
+            libc::signal(libc::SIGINT, handler);
+            libc::signal(libc::SIGQUIT, handler);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = ignore;
+}
+
+/// Create a file only readable by the user, it fails if the file already exists
+/// so another user can't replace it by a link.
+pub fn create_private_file(path: &Path) -> Result<fs::File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        // The content can hold passwords
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .map_err(|e| Error::io(format!("Failed to create '{}'", path.display()), e))
+}
+
+/// Move the file, it's copied then removed when the destination is on another file system.
+pub fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)
+        .and_then(|_| fs::remove_file(from))
+        .map_err(|e| {
+            Error::io(
+                format!("Failed to move '{}' to '{}'", from.display(), to.display()),
+                e,
+            )
+        })
+}
+
+/// Edit the content in `$VISUAL` or `$EDITOR` (`vi` by default) through a temporary file.<br>
+/// The terminal must be restored before calling it, the editor takes the whole screen.
+pub fn edit_externally(content: &str, extension: &str) -> Result<String> {
+    let mut path = env::temp_dir();
+    path.push(format!(
+        "myshortcuts-{}-{}.{}",
+        std::process::id(),
+        unix_now(),
+        extension
+    ));
+
+    let mut file = create_private_file(&path)?;
+    file.write_all(content.as_bytes())?;
+    drop(file);
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or(String::from("vi"));
+    // The editor can have arguments, like `code --wait`
+    let mut args = editor.split_whitespace();
+    let program = args.next().unwrap_or("vi");
+    let mut command = Command::new(program);
+    command.args(args).arg(&path);
+    // With `--print` the standard output is read by the shell, the editor needs the terminal
+    if !io::stdout().is_terminal()
+        && let Ok(tty) = OpenOptions::new().write(true).open("/dev/tty")
+    {
+        command.stdout(tty);
+    }
+    let status = command.status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(Error::from),
+        Ok(status) => Err(Error::command(format!(
+            "'{}' exited with {}",
+            editor, status
+        ))),
+        Err(error) => Err(Error::io(format!("Failed to run '{}'", editor), error)),
+    };
+    let _ = fs::remove_file(&path);
+
+    // Most editors add a newline at the end of the file
+    result.map(|content| {
+        content
+            .strip_suffix('\n')
+            .map(String::from)
+            .unwrap_or(content)
+    })
+}
+
 pub fn generate_name(current_names: Vec<String>) -> String {
     let nb_max = current_names
         .iter()
@@ -32,112 +368,134 @@ pub fn generate_name(current_names: Vec<String>) -> String {
     format!("Default{}", nb_max + 1)
 }
 
-pub fn neo4j(vector: Vec<&String>) -> String {
-    let mut flags = Vec::new();
-    if let (Some(&host), Some(&port)) = (vector.first(), vector.get(1)) {
-        if !host.is_empty() && !port.is_empty() {
-            flags.push(format!("-a neo4j://{}:{}", host, port));
-        }
+/// Replace every non-empty secret of the command by `****`.<br>
+/// The longest ones are replaced first, so a secret holding another one is fully masked.
+pub fn mask_secrets(command: &str, secrets: &[&String]) -> String {
+    let mut secrets = secrets.iter().filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    secrets
+        .into_iter()
+        .fold(command.to_string(), |command, secret| {
+            command.replace(secret.as_str(), "****")
+        })
+}
+
+/// Detect the fallback emitted by the builders when the arguments are inconsistent.
+pub fn get_problem(command: &str) -> Option<&str> {
+    command
+        .strip_prefix("echo '")
+        .filter(|c| c.starts_with("Inconsistent"))
+        .and_then(|c| c.split(" : ").next())
+}
+
+pub fn neo4j(vector: Vec<&String>) -> ShellCommand {
+    let mut command = ShellCommand::new("cypher-shell");
+    if let (Some(&host), Some(&port)) = (vector.first(), vector.get(1))
+        && !host.is_empty()
+        && !port.is_empty()
+    {
+        command = command.flag("-a", &format!("neo4j://{}:{}", host, port));
     }
     for &(index, flag) in &[(2, "-u"), (3, "-p"), (4, "-d"), (5, "-f")] {
-        if let Some(&value) = vector.get(index) {
-            if !value.is_empty() {
-                flags.push(format!("{} {}", flag, value));
-            }
+        if let Some(&value) = vector.get(index)
+            && !value.is_empty()
+        {
+            command = command.flag(flag, value);
         }
     }
-    format!("cypher-shell {}", flags.join(" "))
+    command
 }
 
-pub fn postgresql(vector: Vec<&String>) -> String {
-    let mut command = Vec::new();
-
-    if let Some(password) = vector.get(3).filter(|s| !s.is_empty()) {
-        command.push(format!("export PGPASSWORD='{}' &&", password));
-    }
-    command.push("psql".to_string());
+pub fn postgresql(vector: Vec<&String>) -> ShellCommand {
+    let mut command = ShellCommand::new("psql");
 
     for &(index, flag) in &[(0, "-h"), (1, "-p"), (2, "-U"), (4, "-d"), (5, "-f")] {
         if let Some(value) = vector.get(index).filter(|s| !s.is_empty()) {
-            command.push(format!("{} {}", flag, value));
+            command = command.flag(flag, value);
         }
     }
-    command.join(" ")
+    match vector.get(3).filter(|s| !s.is_empty()) {
+        Some(password) => command.with_env("PGPASSWORD", password),
+        None => command,
+    }
 }
 
-pub fn mysql(vector: Vec<&String>) -> String {
-    let mut flags = Vec::new();
+pub fn mysql(vector: Vec<&String>) -> ShellCommand {
+    let mut command = ShellCommand::new("mysql");
 
     for &(index, flag) in &[(0, "-h"), (1, "-P"), (2, "-u")] {
         if let Some(value) = vector.get(index).filter(|s| !s.is_empty()) {
-            flags.push(format!("{} {}", flag, value));
+            command = command.flag(flag, value);
         }
     }
+    // The password must be glued to the flag, `-p` alone asks for it
     if let Some(value) = vector.get(3).filter(|s| !s.is_empty()) {
-        flags.push(format!("-p '{}'", value));
+        command = command.arg(&format!("-p{}", value));
     }
     if let Some(value) = vector.get(5).filter(|s| !s.is_empty()) {
-        flags.push(format!("--protocol=socket -S {}", value))
+        command = command.arg("--protocol=socket").flag("-S", value);
     }
     if let Some(value) = vector.get(4).filter(|s| !s.is_empty()) {
-        flags.push(String::clone(value));
+        command = command.arg(value);
     }
-    if let Some(value) = vector.get(6).filter(|s| !s.is_empty()) {
-        flags.push(format!("< {}", value));
+    match vector.get(6).filter(|s| !s.is_empty()) {
+        Some(script_path) => command.with_input(script_path),
+        None => command,
     }
-    format!("mysql {}", flags.join(" "))
 }
 
-pub fn mariadb(vector: Vec<&String>) -> String {
-    mysql(vector).replace("mysql", "mariadb")
+pub fn mariadb(vector: Vec<&String>) -> ShellCommand {
+    let mut command = mysql(vector);
+    command.replace_program("mariadb");
+    command
 }
 
-pub fn sqlite(vector: Vec<&String>) -> String {
+pub fn sqlite(vector: Vec<&String>) -> ShellCommand {
     match (
         vector.first().filter(|s| !s.is_empty()),
         vector.get(1).filter(|s| !s.is_empty()),
     ) {
-        (Some(db_path), Some(script_path)) => format!("sqlite3 {} < {}", db_path, script_path),
-        (Some(db_path), None) => format!("sqlite3 {}", db_path),
-        _ => format!("echo 'Inconsistent SQLite arguments : {:?}'", vector),
+        (Some(db_path), Some(script_path)) => ShellCommand::new("sqlite3")
+            .arg(db_path)
+            .with_input(script_path),
+        (Some(db_path), None) => ShellCommand::new("sqlite3").arg(db_path),
+        _ => {
+            ShellCommand::new("echo").arg(&format!("Inconsistent SQLite arguments : {:?}", vector))
+        }
     }
 }
 
-pub fn oracle(vector: Vec<&String>) -> String {
-    let mut command = String::new();
-    match (
+pub fn oracle(vector: Vec<&String>) -> ShellCommand {
+    let mut connection = match (
         vector.first().filter(|s| !s.is_empty()),
         vector.get(1).filter(|s| !s.is_empty()),
+        vector.get(2).filter(|s| !s.is_empty()),
+        vector.get(3).filter(|s| !s.is_empty()),
     ) {
-        (Some(host), Some(port)) => {
-            match (
-                vector.get(2).filter(|s| !s.is_empty()),
-                vector.get(3).filter(|s| !s.is_empty()),
-            ) {
-                (Some(username), Some(password)) => {
-                    command.push_str(&format!(
-                        "sqlplus {}/{}@{}:{}",
-                        username, password, host, port
-                    ));
-                }
-                _ => return format!("echo 'Inconsistent Oracle arguments : {:?}'", vector),
-            }
+        (Some(host), Some(port), Some(username), Some(password)) => {
+            format!("{}/{}@{}:{}", username, password, host, port)
         }
-        _ => return format!("echo 'Inconsistent Oracle arguments : {:?}'", vector),
-    }
+        _ => {
+            return ShellCommand::new("echo")
+                .arg(&format!("Inconsistent Oracle arguments : {:?}", vector));
+        }
+    };
 
     if let Some(database) = vector.get(4).filter(|s| !s.is_empty()) {
-        command.push_str(&format!("/{}", database));
+        connection.push_str(&format!("/{}", database));
     }
-    if let Some(script_path) = vector.get(5).filter(|s| !s.is_empty()) {
-        command = command.replace("sqlplus", "echo exit | sqlplus -s ");
-        command.push_str(&format!(" @{}", script_path));
+    match vector.get(5).filter(|s| !s.is_empty()) {
+        Some(script_path) => ShellCommand::new("sqlplus")
+            .arg("-s")
+            .arg(&connection)
+            .arg(&format!("@{}", script_path))
+            .with_text("exit"),
+        None => ShellCommand::new("sqlplus").arg(&connection),
     }
-    command
 }
 
-pub fn mongodb(vector: Vec<&String>) -> String {
-    let mut flags = Vec::new();
+pub fn mongodb(vector: Vec<&String>) -> ShellCommand {
+    let mut command = ShellCommand::new("mongosh");
     for &(index, flag) in &[
         (0, "--host"),
         (1, "--port"),
@@ -147,14 +505,14 @@ pub fn mongodb(vector: Vec<&String>) -> String {
         (5, "-f"),
     ] {
         if let Some(value) = vector.get(index).filter(|s| !s.is_empty()) {
-            flags.push(format!("{} {}", flag, value));
+            command = command.flag(flag, value);
         }
     }
-    format!("mongosh {}", flags.join(" "))
+    command
 }
 
-pub fn redis(vector: Vec<&String>) -> String {
-    let mut flags = Vec::new();
+pub fn redis(vector: Vec<&String>) -> ShellCommand {
+    let mut command = ShellCommand::new("redis-cli");
     for &(index, flag) in &[
         (0, "-h"),
         (1, "-p"),
@@ -164,10 +522,10 @@ pub fn redis(vector: Vec<&String>) -> String {
         (5, "--eval"),
     ] {
         if let Some(value) = vector.get(index).filter(|s| !s.is_empty()) {
-            flags.push(format!("{} {}", flag, value));
+            command = command.flag(flag, value);
         }
     }
-    format!("redis-cli {}", flags.join(" "))
+    command
 }
 
 #[macro_use]
@@ -180,7 +538,7 @@ pub mod macros {
         ($vector: expr) => {
             $vector
                 .into_iter()
-                .map(|e| format!("{};", e.get_value()))
+                .map(|e| format!("{};", e.encode()))
                 .collect::<String>()
         };
     }
@@ -189,12 +547,133 @@ pub mod macros {
     macro_rules! filter_config {
         ($vector: expr) => {{
             let mut result = $vector;
-            let mut last_index = result.len() - 1;
-            while result[last_index] == "" || result[last_index] == "\n" {
+            while result.last().is_some_and(|e| *e == "" || *e == "\n") {
                 result.pop();
-                last_index -= 1usize;
             }
             result
         }};
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::Configuration;
+
+    #[test]
+    fn valid_names() {
+        assert!(is_valid_name("prod-pg (1)"));
+        assert!(is_valid_name("café"));
+        assert!(!is_valid_name("a;b"));
+        assert!(!is_valid_name("a\nb"));
+        assert!(!is_valid_name("a\tb"));
+    }
+
+    #[test]
+    fn decode_config_of_the_escaped_values() {
+        assert_eq!(
+            decode_config(r"a\;b;c\\d;e\nf;"),
+            vec![
+                Some(String::from("a;b")),
+                Some(String::from(r"c\d")),
+                Some(String::from("e\nf"))
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_config_of_the_unset_and_empty_values() {
+        assert_eq!(
+            decode_config(r"\u;;x;\u"),
+            vec![None, Some(String::new()), Some(String::from("x")), None]
+        );
+        assert!(decode_config("").is_empty());
+    }
+
+    #[test]
+    fn encode_then_decode_config() {
+        let configurations = vec![
+            Configuration::from("db;prod", "Host"),
+            Configuration::from(r"C:\dir\", "Path"),
+            Configuration::from("line 1\nline 2", "Command"),
+            Configuration::from(r"\u", "Password"),
+            Configuration::from("", "Database"),
+            Configuration::unset("Username"),
+        ];
+        let decoded = decode_config(&format_config!(&configurations));
+        assert_eq!(
+            decoded,
+            vec![
+                Some(String::from("db;prod")),
+                Some(String::from(r"C:\dir\")),
+                Some(String::from("line 1\nline 2")),
+                Some(String::from(r"\u")),
+                Some(String::new()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_date_of_the_epoch() {
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("1969-12-31").unwrap(), -86400);
+        assert_eq!(parse_date("2000-03-01").unwrap(), 951868800);
+    }
+
+    #[test]
+    fn parse_date_of_the_leap_years() {
+        assert_eq!(
+            format_date(parse_date("2024-02-29").unwrap()),
+            "2024-02-29 00:00"
+        );
+        assert_eq!(
+            format_date(parse_date("2000-02-29").unwrap()),
+            "2000-02-29 00:00"
+        );
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("1900-02-29").is_err());
+        assert_eq!(
+            parse_date("2024-03-01").unwrap() - parse_date("2024-02-28").unwrap(),
+            2 * 86400
+        );
+    }
+
+    #[test]
+    fn parse_date_of_the_month_ends() {
+        for (last, next) in [
+            ("2026-01-31", "2026-02-01"),
+            ("2026-02-28", "2026-03-01"),
+            ("2026-04-30", "2026-05-01"),
+            ("2026-12-31", "2027-01-01"),
+        ] {
+            assert_eq!(parse_date(last).unwrap() + 86400, parse_date(next).unwrap());
+        }
+        assert!(parse_date("2026-04-31").is_err());
+        assert!(parse_date("2026-02-30").is_err());
+        assert!(parse_date("2026-13-01").is_err());
+        assert!(parse_date("2026-01-00").is_err());
+    }
+
+    #[test]
+    fn parse_date_of_invalid_formats() {
+        for value in [
+            "",
+            "2026",
+            "2026-01",
+            "2026-01-01-01",
+            "2026/01/01",
+            "tuesday",
+        ] {
+            assert!(parse_date(value).is_err(), "'{}' was accepted", value);
+        }
+    }
+
+    #[test]
+    fn format_date_in_utc() {
+        assert_eq!(format_date(0), "1970-01-01 00:00");
+        assert_eq!(format_date(-60), "1969-12-31 23:59");
+        assert_eq!(format_date(1_772_323_199), "2026-02-28 23:59");
+        assert_eq!(format_date(1_772_323_200), "2026-03-01 00:00");
+    }
+}